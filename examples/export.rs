@@ -1,15 +1,10 @@
-use dockworker::{container::ContainerFilters, Docker};
+use dockworker::Docker;
 
 #[tokio::main]
 async fn main() {
     let docker = Docker::connect_with_defaults().unwrap();
     let mut file = tokio::fs::File::create("temp.tar").await.unwrap();
-    if let Some(container) = docker
-        .list_containers(None, None, None, ContainerFilters::default())
-        .await
-        .unwrap()
-        .get(0)
-    {
+    if let Some(container) = docker.running_containers().await.unwrap().get(0) {
         let res = docker
             .export_container(container.Id.as_str())
             .await
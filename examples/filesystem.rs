@@ -1,14 +1,9 @@
-use dockworker::{container::ContainerFilters, Docker};
+use dockworker::Docker;
 
 #[tokio::main]
 async fn main() {
     let docker = Docker::connect_with_defaults().unwrap();
-    if let Some(container) = docker
-        .list_containers(None, None, None, ContainerFilters::default())
-        .await
-        .unwrap()
-        .get(0)
-    {
+    if let Some(container) = docker.running_containers().await.unwrap().get(0) {
         let changes = docker
             .filesystem_changes(container.Id.as_str())
             .await
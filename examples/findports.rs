@@ -1,14 +1,12 @@
-use dockworker::{container::ContainerFilters, Docker};
+use dockworker::errors::*;
+use dockworker::Docker;
 
 #[tokio::main]
-async fn main() {
-    let docker = Docker::connect_with_defaults().unwrap();
-    let containers = docker
-        .list_containers(Some(true), None, None, ContainerFilters::default())
-        .await
-        .unwrap();
+async fn main() -> Result<()> {
+    let docker = Docker::connect_with_defaults()?;
+    let containers = docker.all_containers().await?;
     for container in &containers {
-        let info = docker.container_info(container.Id.as_str()).await.unwrap();
+        let info = docker.container_info(container.Id.as_str()).await?;
 
         // Uncomment this to dump everything we know about a container.
         //println!("{:#?}", &info);
@@ -18,4 +16,5 @@ async fn main() {
             println!("{k}: {v:?}");
         }
     }
+    Ok(())
 }
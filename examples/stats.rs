@@ -1,12 +1,9 @@
-use dockworker::{container::ContainerFilters, Docker};
+use dockworker::Docker;
 
 #[tokio::main]
 async fn main() {
     let docker = Docker::connect_with_defaults().unwrap();
-    let containers = docker
-        .list_containers(None, None, None, ContainerFilters::default())
-        .await
-        .unwrap();
+    let containers = docker.running_containers().await.unwrap();
     for container in containers {
         let mut stats = docker
             .stats(&container.Id, Some(false), Some(true))
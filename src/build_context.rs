@@ -0,0 +1,365 @@
+//! Helpers to create a build context for `Docker::build_image`
+
+use crate::errors::Error as DwError;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Files sent to the daemon even if `.dockerignore` excludes them,
+/// because the builder needs them.
+const ALWAYS_INCLUDED: &[&str] = &["Dockerfile", ".dockerignore"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Literal(char),
+    /// `?`
+    Any,
+    /// `*`
+    Star,
+    /// `**` not followed by a separator
+    DoubleStar,
+    /// `**/`, which matches zero or more directories
+    DoubleStarDir,
+    /// `[...]`
+    Class {
+        negated: bool,
+        ranges: Vec<(char, char)>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Pattern {
+    tokens: Vec<Token>,
+    exclusion: bool,
+}
+
+/// Patterns of a `.dockerignore` file
+///
+/// Patterns follow the rules of `docker build`: `*` and `?` do not match `/`,
+/// `**` matches any number of directories, a leading `!` re-includes paths
+/// and the last matching pattern wins. A pattern excluding a directory
+/// also excludes everything in it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DockerIgnore {
+    patterns: Vec<Pattern>,
+}
+
+fn bad_pattern(pattern: &str) -> DwError {
+    DwError::Unknown {
+        message: format!("bad .dockerignore pattern: {pattern}"),
+    }
+}
+
+/// Lexically clean a slash separated path like `filepath.Clean` of go
+fn clean_path(path: &str) -> String {
+    let mut components: Vec<&str> = Vec::new();
+    for component in path.split('/') {
+        match component {
+            "" | "." => {}
+            ".." => {
+                components.pop();
+            }
+            component => components.push(component),
+        }
+    }
+    if components.is_empty() {
+        ".".to_owned()
+    } else {
+        components.join("/")
+    }
+}
+
+fn tokenize(pattern: &str) -> Result<Vec<Token>, DwError> {
+    let mut tokens = Vec::new();
+    let mut chars = pattern.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                while chars.peek() == Some(&'*') {
+                    chars.next();
+                }
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    tokens.push(Token::DoubleStarDir);
+                } else {
+                    tokens.push(Token::DoubleStar);
+                }
+            }
+            '*' => tokens.push(Token::Star),
+            '?' => tokens.push(Token::Any),
+            '\\' => tokens.push(Token::Literal(
+                chars.next().ok_or_else(|| bad_pattern(pattern))?,
+            )),
+            '[' => {
+                let negated = chars.peek() == Some(&'^');
+                if negated {
+                    chars.next();
+                }
+                let mut ranges = Vec::new();
+                loop {
+                    let lo = match chars.next().ok_or_else(|| bad_pattern(pattern))? {
+                        ']' if !ranges.is_empty() => break,
+                        '\\' => chars.next().ok_or_else(|| bad_pattern(pattern))?,
+                        c => c,
+                    };
+                    let hi = if chars.peek() == Some(&'-') {
+                        chars.next();
+                        match chars.next().ok_or_else(|| bad_pattern(pattern))? {
+                            '\\' => chars.next().ok_or_else(|| bad_pattern(pattern))?,
+                            c => c,
+                        }
+                    } else {
+                        lo
+                    };
+                    ranges.push((lo, hi));
+                }
+                tokens.push(Token::Class { negated, ranges });
+            }
+            c => tokens.push(Token::Literal(c)),
+        }
+    }
+    Ok(tokens)
+}
+
+fn matches(tokens: &[Token], text: &[char]) -> bool {
+    match tokens.split_first() {
+        None => text.is_empty(),
+        Some((Token::Literal(c), rest)) => text.first() == Some(c) && matches(rest, &text[1..]),
+        Some((Token::Any, rest)) => {
+            matches!(text.first(), Some(&c) if c != '/') && matches(rest, &text[1..])
+        }
+        Some((Token::Class { negated, ranges }, rest)) => {
+            matches!(text.first(), Some(&c) if c != '/'
+                && ranges.iter().any(|&(lo, hi)| lo <= c && c <= hi) != *negated)
+                && matches(rest, &text[1..])
+        }
+        Some((Token::Star, rest)) => (0..=text.len())
+            .take_while(|&i| i == 0 || text[i - 1] != '/')
+            .any(|i| matches(rest, &text[i..])),
+        Some((Token::DoubleStar, rest)) => (0..=text.len()).any(|i| matches(rest, &text[i..])),
+        Some((Token::DoubleStarDir, rest)) => {
+            matches(rest, text)
+                || (1..=text.len())
+                    .filter(|&i| text[i - 1] == '/')
+                    .any(|i| matches(rest, &text[i..]))
+        }
+    }
+}
+
+impl Pattern {
+    fn matches(&self, path: &str) -> bool {
+        matches(&self.tokens, &path.chars().collect::<Vec<_>>())
+    }
+}
+
+impl DockerIgnore {
+    /// Parse the content of a `.dockerignore` file
+    pub fn parse(content: &str) -> Result<Self, DwError> {
+        let mut patterns = Vec::new();
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (exclusion, pattern) = match line.strip_prefix('!') {
+                Some(pattern) => (true, pattern.trim()),
+                None => (false, line),
+            };
+            let pattern = clean_path(pattern);
+            if pattern == "." {
+                continue;
+            }
+            patterns.push(Pattern {
+                tokens: tokenize(&pattern)?,
+                exclusion,
+            });
+        }
+        Ok(Self { patterns })
+    }
+
+    /// Whether a slash separated path relative to the context root is excluded
+    pub fn is_excluded(&self, path: &str) -> bool {
+        let parents: Vec<&str> = path.match_indices('/').map(|(i, _)| &path[..i]).collect();
+        let mut excluded = false;
+        for pattern in &self.patterns {
+            // an exclusion only matters for excluded paths, and vice versa
+            if pattern.exclusion != excluded {
+                continue;
+            }
+            if pattern.matches(path) || parents.iter().any(|parent| pattern.matches(parent)) {
+                excluded = !pattern.exclusion;
+            }
+        }
+        excluded
+    }
+
+    fn has_exclusions(&self) -> bool {
+        self.patterns.iter().any(|pattern| pattern.exclusion)
+    }
+}
+
+fn append_dir<W: io::Write>(
+    builder: &mut tar::Builder<W>,
+    root: &Path,
+    dir: &str,
+    ignore: &DockerIgnore,
+) -> Result<(), DwError> {
+    let mut entries = fs::read_dir(root.join(dir))?.collect::<Result<Vec<_>, _>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+    for entry in entries {
+        let name = entry.file_name();
+        let name = name.to_str().ok_or_else(|| DwError::Unknown {
+            message: format!("convert to String: {name:?}"),
+        })?;
+        let path = if dir.is_empty() {
+            name.to_owned()
+        } else {
+            format!("{dir}/{name}")
+        };
+        let excluded = ignore.is_excluded(&path) && !ALWAYS_INCLUDED.contains(&path.as_str());
+        if entry.file_type()?.is_dir() {
+            // files in an excluded directory may be re-included by a later pattern
+            if excluded && !ignore.has_exclusions() {
+                continue;
+            }
+            if !excluded {
+                builder.append_path_with_name(entry.path(), &path)?;
+            }
+            append_dir(builder, root, &path, ignore)?;
+        } else if !excluded {
+            builder.append_path_with_name(entry.path(), &path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Create a tar archive of the directory `dir` to be used as a build context
+///
+/// # Summary
+/// If `dockerignore` is true, paths matching `dir/.dockerignore` are left out.
+/// `Dockerfile` and `.dockerignore` are always included.
+/// Symbolic links are archived as links.
+///
+/// The archive is created in the temporary directory and its path is returned.
+/// The caller is responsible for removing it.
+pub fn tar_directory(dir: &Path, dockerignore: bool) -> Result<PathBuf, DwError> {
+    let ignore = if dockerignore {
+        match fs::read_to_string(dir.join(".dockerignore")) {
+            Ok(content) => DockerIgnore::parse(&content)?,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => DockerIgnore::default(),
+            Err(err) => return Err(err.into()),
+        }
+    } else {
+        DockerIgnore::default()
+    };
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+    let path = std::env::temp_dir().join(format!(
+        "dockworker-build-context-{}-{}.tar",
+        std::process::id(),
+        nanos
+    ));
+    let mut builder = tar::Builder::new(fs::File::create(&path)?);
+    builder.follow_symlinks(false);
+    let res = append_dir(&mut builder, dir, "", &ignore)
+        .and_then(|()| builder.into_inner().map(|_| ()).map_err(Into::into));
+    if let Err(err) = res {
+        let _ = fs::remove_file(&path);
+        return Err(err);
+    }
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dockerignore_patterns() {
+        let ignore = DockerIgnore::parse(
+            r#"
+# comment
+/target
+*.log
+**/*.tmp
+docs/?.md
+!docs/keep.log
+src/[a-c]*.rs
+"#,
+        )
+        .unwrap();
+        let cases = [
+            ("target", true),
+            ("target/debug/app", true),
+            ("app.log", true),
+            ("logs/app.log", false),
+            ("a.tmp", true),
+            ("a/b/c.tmp", true),
+            ("docs/a.md", true),
+            ("docs/ab.md", false),
+            ("docs/keep.log", false),
+            ("src/build.rs", true),
+            ("src/main.rs", false),
+            ("Dockerfile", false),
+        ];
+        for (path, excluded) in cases {
+            assert_eq!(ignore.is_excluded(path), excluded, "{path}");
+        }
+    }
+
+    #[test]
+    fn dockerignore_reinclude() {
+        let ignore = DockerIgnore::parse("*.md\n!README*.md\nREADME-secret.md").unwrap();
+        assert!(ignore.is_excluded("CHANGELOG.md"));
+        assert!(!ignore.is_excluded("README.md"));
+        assert!(ignore.is_excluded("README-secret.md"));
+    }
+
+    #[test]
+    fn dockerignore_bad_pattern() {
+        assert!(DockerIgnore::parse("[abc").is_err());
+        assert!(DockerIgnore::parse("abc\\").is_err());
+    }
+
+    #[test]
+    fn tar_directory_with_dockerignore() {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("dockworker-test-context-{nanos}"));
+        fs::create_dir_all(dir.join("target/debug")).unwrap();
+        fs::create_dir_all(dir.join("src")).unwrap();
+        fs::write(dir.join("Dockerfile"), "FROM alpine\n").unwrap();
+        fs::write(dir.join(".dockerignore"), "target\n*.log\nDockerfile\n").unwrap();
+        fs::write(dir.join("src/main.rs"), "fn main() {}\n").unwrap();
+        fs::write(dir.join("target/debug/app"), "").unwrap();
+        fs::write(dir.join("build.log"), "").unwrap();
+
+        let archive = tar_directory(&dir, true).unwrap();
+        let mut entries = tar::Archive::new(fs::File::open(&archive).unwrap())
+            .entries()
+            .unwrap()
+            .map(|entry| entry.unwrap().path().unwrap().display().to_string())
+            .collect::<Vec<_>>();
+        entries.sort();
+        assert_eq!(
+            entries,
+            vec![".dockerignore", "Dockerfile", "src", "src/main.rs"]
+        );
+
+        let archive_all = tar_directory(&dir, false).unwrap();
+        let count = tar::Archive::new(fs::File::open(&archive_all).unwrap())
+            .entries()
+            .unwrap()
+            .count();
+        assert_eq!(count, 8);
+
+        fs::remove_file(archive).unwrap();
+        fs::remove_file(archive_all).unwrap();
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,9 +1,14 @@
 use crate::network::EndpointConfig;
-use serde::de::{self, DeserializeOwned, Deserializer};
+use crate::options::{LabelSelector, RestartPolicy};
+use crate::serde_helpers::null_to_default;
+use chrono::{DateTime, Datelike, FixedOffset, Utc};
+use serde::de::Deserializer;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fmt;
+use std::path::Path;
 use std::str::FromStr;
+use std::time::Duration;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[allow(non_snake_case)]
@@ -15,6 +20,8 @@ pub struct Container {
     pub Status: String,
     pub Command: String,
     pub Created: u64,
+    /// Names of the container with the leading `/` (e.g. `/gifted_turing`).
+    /// See `names_trimmed` for names without it.
     pub Names: Vec<String>,
     pub Ports: Vec<Port>,
     pub SizeRw: Option<u64>, // I guess it is optional on Mac.
@@ -25,6 +32,16 @@ pub struct Container {
     pub Mounts: Option<Vec<Mount>>,
 }
 
+impl Container {
+    /// `Names` without the leading `/`
+    pub fn names_trimmed(&self) -> Vec<&str> {
+        self.Names
+            .iter()
+            .map(|name| name.strip_prefix('/').unwrap_or(name))
+            .collect()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 #[allow(non_snake_case)]
 pub struct Port {
@@ -39,6 +56,10 @@ pub struct Port {
 pub enum PortType {
     Tcp,
     Udp,
+    Sctp,
+    /// unknown protocol
+    #[serde(other)]
+    Unknown,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
@@ -47,6 +68,19 @@ pub struct HostConfig {
     pub NetworkMode: String,
 }
 
+/// Host configuration in the result of inspecting a container
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[allow(non_snake_case)]
+pub struct HostConfigInfo {
+    #[serde(default)]
+    pub NetworkMode: String,
+    /// The same type as the one passed on create and update
+    #[serde(default)]
+    pub RestartPolicy: RestartPolicy,
+    #[serde(default)]
+    pub AutoRemove: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[allow(non_snake_case)]
 pub struct SummaryNetworkSettings {
@@ -63,7 +97,8 @@ pub struct ContainerInfo {
     pub Driver: String,
     // ExecIDs
     // GraphDriver
-    // HostConfig
+    #[serde(default)]
+    pub HostConfig: HostConfigInfo,
     pub HostnamePath: String,
     pub HostsPath: String,
     pub Id: String,
@@ -137,13 +172,23 @@ pub struct Config {
     #[serde(deserialize_with = "null_to_default")]
     pub Volumes: HashMap<String, UnspecifiedObject>,
     pub WorkingDir: String,
+    /// Signal to stop the container
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub StopSignal: Option<String>,
+    /// Timeout to stop the container in seconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub StopTimeout: Option<i64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 #[allow(non_snake_case)]
 pub struct Mount {
-    // Name (optional)
-    // Driver (optional)
+    /// Missing in the responses of older daemons
+    pub Type: Option<MountType>,
+    /// Name of the volume, if this is a volume mount
+    pub Name: Option<String>,
+    /// Volume driver, if this is a volume mount
+    pub Driver: Option<String>,
     pub Source: String,
     pub Destination: String,
     pub Mode: String,
@@ -151,6 +196,19 @@ pub struct Mount {
     pub Propagation: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum MountType {
+    Bind,
+    Volume,
+    Tmpfs,
+    Npipe,
+    Cluster,
+    /// unknown mount type
+    #[serde(other)]
+    Unknown,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[allow(non_snake_case)]
 pub struct NetworkSettings {
@@ -205,6 +263,8 @@ pub enum HealthState {
     Healthy,
     /// Indicates that the container has a problem
     Unhealthy,
+    /// Unknown state reported by a newer daemon
+    Unknown,
 }
 
 impl fmt::Display for HealthState {
@@ -214,6 +274,7 @@ impl fmt::Display for HealthState {
             HealthState::Starting => write!(f, "starting"),
             HealthState::Healthy => write!(f, "healthy"),
             HealthState::Unhealthy => write!(f, "unhealthy"),
+            HealthState::Unknown => write!(f, "unknown"),
         }
     }
 }
@@ -224,7 +285,7 @@ impl<'de> Deserialize<'de> for HealthState {
         D: Deserializer<'de>,
     {
         let s = String::deserialize(deserializer)?;
-        s.parse().map_err(de::Error::custom)
+        Ok(s.parse().unwrap_or(HealthState::Unknown))
     }
 }
 
@@ -270,12 +331,191 @@ pub struct State {
     pub Health: Option<Health>,
 }
 
+/// Summary of failures of a container created by `ContainerInfo::failure_summary`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FailureSummary {
+    /// number of restarts by the restart policy
+    pub restart_count: u64,
+    /// exit code of the last run
+    pub exit_code: i64,
+    /// error message of the last run, `None` if empty
+    pub error: Option<String>,
+    /// whether the last run was killed for out of memory
+    pub oom_killed: bool,
+    /// time when the last run finished, `None` if the container has never finished
+    pub finished_at: Option<DateTime<FixedOffset>>,
+}
+
+/// Parse a timestamp of `State`, mapping the zero time of go (`0001-01-01T00:00:00Z`) to `None`
+fn parse_state_time(time: &str) -> Option<DateTime<FixedOffset>> {
+    DateTime::parse_from_rfc3339(time)
+        .ok()
+        .filter(|time| time.naive_utc().year() > 1)
+}
+
+impl State {
+    /// `StartedAt` or `None` if the container has never been started
+    pub fn started_at(&self) -> Option<DateTime<FixedOffset>> {
+        parse_state_time(&self.StartedAt)
+    }
+
+    /// `FinishedAt` or `None` if the container has never finished
+    pub fn finished_at(&self) -> Option<DateTime<FixedOffset>> {
+        parse_state_time(&self.FinishedAt)
+    }
+
+    /// Time elapsed since the container started, or `None` if it is not running
+    pub fn uptime(&self) -> Option<Duration> {
+        if !self.Running {
+            return None;
+        }
+        (Utc::now() - self.started_at()?.with_timezone(&Utc))
+            .to_std()
+            .ok()
+    }
+
+    /// Typed representation of `Status`
+    ///
+    /// A status unknown to this crate is mapped to `ContainerState::Unknown`.
+    pub fn status_enum(&self) -> ContainerState {
+        self.Status.parse().unwrap_or(ContainerState::Unknown)
+    }
+}
+
 impl std::fmt::Display for Container {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::result::Result<(), std::fmt::Error> {
         write!(f, "{}", self.Id)
     }
 }
 
+impl ContainerInfo {
+    /// `Config.Env` as a map
+    ///
+    /// An entry without `=` is mapped to an empty value.
+    pub fn env_map(&self) -> HashMap<String, String> {
+        self.Config
+            .Env
+            .iter()
+            .map(|entry| match entry.split_once('=') {
+                Some((key, value)) => (key.to_owned(), value.to_owned()),
+                None => (entry.to_owned(), String::new()),
+            })
+            .collect()
+    }
+
+    /// Value of the label `key` in `Config.Labels`
+    pub fn label(&self, key: &str) -> Option<&str> {
+        self.Config.Labels.get(key).map(String::as_str)
+    }
+
+    /// Host path of the log file of the container
+    ///
+    /// `LogPath` is only populated for the `json-file` and `local` logging drivers.
+    /// Returns `None` when it is empty.
+    pub fn log_path(&self) -> Option<&Path> {
+        if self.LogPath.is_empty() {
+            None
+        } else {
+            Some(Path::new(&self.LogPath))
+        }
+    }
+
+    /// Command line actually running, resolved from the entrypoint and cmd
+    ///
+    /// This is `Path` followed by `Args`.
+    pub fn command_line(&self) -> Vec<String> {
+        std::iter::once(self.Path.clone())
+            .chain(self.Args.iter().cloned())
+            .collect()
+    }
+
+    /// Entrypoint in effect
+    ///
+    /// This is `Config.Entrypoint`, or `Path` if the container has no entrypoint,
+    /// in which case the first element of `Config.Cmd` is executed.
+    pub fn effective_entrypoint(&self) -> Vec<String> {
+        if self.Config.Entrypoint.is_empty() {
+            vec![self.Path.clone()]
+        } else {
+            self.Config.Entrypoint.clone()
+        }
+    }
+
+    /// IPv4 address of the container on the network `network`
+    ///
+    /// Returns `None` if the container is not connected to the network
+    /// or has no address on it (e.g. it is not running).
+    pub fn ip_address(&self, network: &str) -> Option<&str> {
+        self.NetworkSettings
+            .Networks
+            .get(network)
+            .map(|network| network.IPAddress.as_str())
+            .filter(|address| !address.is_empty())
+    }
+
+    /// Network name to IPv4 address mapping of the container
+    ///
+    /// Networks on which the container has no address are omitted.
+    pub fn ip_addresses(&self) -> HashMap<String, String> {
+        self.NetworkSettings
+            .Networks
+            .iter()
+            .filter(|(_, network)| !network.IPAddress.is_empty())
+            .map(|(name, network)| (name.clone(), network.IPAddress.clone()))
+            .collect()
+    }
+
+    /// Whether the container is restarting after having restarted `threshold` times or more
+    ///
+    /// This is a heuristic over `RestartCount` and `State`,
+    /// which flags a container repeatedly crashing under a restart policy.
+    pub fn is_crash_looping(&self, threshold: u64) -> bool {
+        let restarting = self.State.Restarting || self.State.Status == "restarting";
+        restarting && self.RestartCount >= threshold
+    }
+
+    /// Restart count and the last exit of the container
+    pub fn failure_summary(&self) -> FailureSummary {
+        FailureSummary {
+            restart_count: self.RestartCount,
+            exit_code: self.State.ExitCode,
+            error: Some(self.State.Error.clone()).filter(|error| !error.is_empty()),
+            oom_killed: self.State.OOMKilled,
+            finished_at: self.State.finished_at(),
+        }
+    }
+
+    /// Maximum number of restarts of the `on-failure` restart policy
+    ///
+    /// Returns `None` for the other policies and for unlimited retries.
+    pub fn max_restart_retries(&self) -> Option<u16> {
+        let policy = &self.HostConfig.RestartPolicy;
+        if policy.Name == "on-failure" && policy.MaximumRetryCount > 0 {
+            Some(policy.MaximumRetryCount)
+        } else {
+            None
+        }
+    }
+
+    /// First host port bound to the port `container_port/proto` of the container
+    ///
+    /// Returns `None` if the port is not published.
+    pub fn host_port(&self, container_port: u16, proto: PortType) -> Option<u16> {
+        let proto = match proto {
+            PortType::Tcp => "tcp",
+            PortType::Udp => "udp",
+            PortType::Sctp => "sctp",
+            PortType::Unknown => return None,
+        };
+        self.NetworkSettings
+            .Ports
+            .get(&format!("{container_port}/{proto}"))?
+            .as_ref()?
+            .iter()
+            .find_map(|mapping| mapping.HostPort.parse().ok())
+    }
+}
+
 impl std::fmt::Display for ContainerInfo {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::result::Result<(), std::fmt::Error> {
         write!(f, "{}", self.Id)
@@ -292,8 +532,58 @@ pub enum ContainerStatus {
     Paused,
     Exited,
     Dead,
+    /// Unknown status reported by a newer daemon.
+    /// This value should not be used as a filter.
+    Unknown,
 }
 
+impl fmt::Display for ContainerStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ContainerStatus::Created => write!(f, "created"),
+            ContainerStatus::Restarting => write!(f, "restarting"),
+            ContainerStatus::Running => write!(f, "running"),
+            ContainerStatus::Removing => write!(f, "removing"),
+            ContainerStatus::Paused => write!(f, "paused"),
+            ContainerStatus::Exited => write!(f, "exited"),
+            ContainerStatus::Dead => write!(f, "dead"),
+            ContainerStatus::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ContainerStatus {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        Ok(s.parse().unwrap_or(ContainerStatus::Unknown))
+    }
+}
+
+impl FromStr for ContainerStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "created" => Ok(ContainerStatus::Created),
+            "restarting" => Ok(ContainerStatus::Restarting),
+            "running" => Ok(ContainerStatus::Running),
+            "removing" => Ok(ContainerStatus::Removing),
+            "paused" => Ok(ContainerStatus::Paused),
+            "exited" => Ok(ContainerStatus::Exited),
+            "dead" => Ok(ContainerStatus::Dead),
+            _ => Err(format!(
+                "Cannot parse {s} into known ContainerStatus variant!"
+            )),
+        }
+    }
+}
+
+/// State of a container as reported in `State.Status`
+pub type ContainerState = ContainerStatus;
+
 #[derive(Debug, Clone, PartialEq, PartialOrd, Eq, Ord, Serialize, Default)]
 pub struct ContainerFilters {
     #[serde(skip_serializing_if = "Vec::is_empty")]
@@ -302,6 +592,8 @@ pub struct ContainerFilters {
     name: Vec<String>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     status: Vec<ContainerStatus>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    label: Vec<LabelSelector>,
 }
 
 impl ContainerFilters {
@@ -323,6 +615,12 @@ impl ContainerFilters {
         self.status.push(status);
         self
     }
+
+    /// filter by a label key (`key`) or a key and its value (`key=value`)
+    pub fn label<L: Into<LabelSelector>>(&mut self, label: L) -> &mut Self {
+        self.label.push(label.into());
+        self
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -338,7 +636,38 @@ pub struct AttachResponseFrame {
     pub frame: Vec<u8>,
 }
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+impl AttachResponseFrame {
+    /// Length of the payload, as written in the header of the frame
+    pub fn payload_len(&self) -> usize {
+        self.frame.len()
+    }
+
+    /// The 8-byte header of the frame in the stdcopy format of the daemon
+    ///
+    /// # Panics
+    /// Panics if the payload is longer than `u32::MAX` bytes.
+    pub fn header(&self) -> [u8; 8] {
+        let stream_type = match self.type_ {
+            ContainerStdioType::Stdin => 0,
+            ContainerStdioType::Stdout => 1,
+            ContainerStdioType::Stderr => 2,
+        };
+        let len = u32::try_from(self.frame.len()).expect("payload too large for a stdcopy frame");
+        let mut header = [stream_type, 0, 0, 0, 0, 0, 0, 0];
+        header[4..].copy_from_slice(&len.to_be_bytes());
+        header
+    }
+
+    /// Encode the frame in the stdcopy format, i.e. the header followed by the payload
+    pub fn to_stdcopy(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(8 + self.frame.len());
+        buf.extend_from_slice(&self.header());
+        buf.extend_from_slice(&self.frame);
+        buf
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 #[allow(non_snake_case)]
 pub struct ExitStatus {
     StatusCode: i32,
@@ -362,19 +691,43 @@ impl From<i32> for ExitStatus {
     }
 }
 
-fn null_to_default<'de, D, T>(de: D) -> std::result::Result<T, D::Error>
-where
-    D: Deserializer<'de>,
-    T: DeserializeOwned + Default,
-{
-    let actual: Option<T> = Option::deserialize(de)?;
-    Ok(actual.unwrap_or_default())
-}
-
 #[cfg(test)]
 mod test {
     use super::*;
 
+    #[test]
+    fn ser_container_filters_label() {
+        let mut filters = ContainerFilters::new();
+        filters
+            .label("com.example.vendor")
+            .label(LabelSelector::key_value("com.example.version", "1.0"));
+        assert_eq!(
+            serde_json::to_string(&filters).unwrap(),
+            r#"{"label":["com.example.vendor","com.example.version=1.0"]}"#
+        );
+    }
+
+    #[test]
+    fn deser_unknown_enum_values() {
+        assert_eq!(
+            serde_json::from_str::<HealthState>(r#""healthy""#).unwrap(),
+            HealthState::Healthy
+        );
+        assert_eq!(
+            serde_json::from_str::<HealthState>(r#""degraded""#).unwrap(),
+            HealthState::Unknown
+        );
+        assert!("degraded".parse::<HealthState>().is_err());
+        assert_eq!(
+            serde_json::from_str::<ContainerStatus>(r#""exited""#).unwrap(),
+            ContainerStatus::Exited
+        );
+        assert_eq!(
+            serde_json::from_str::<ContainerStatus>(r#""hibernating""#).unwrap(),
+            ContainerStatus::Unknown
+        );
+    }
+
     // https://github.com/idein/dockworker/issues/84
     #[test]
     fn serde_network() {
@@ -1,43 +1,56 @@
 #![allow(clippy::bool_assert_comparison)]
 use crate::container::{
-    AttachResponseFrame, Container, ContainerFilters, ContainerInfo, ContainerStdioType, ExecInfo,
-    ExitStatus,
+    AttachResponseFrame, Config, Container, ContainerFilters, ContainerInfo, ContainerStatus,
+    ContainerStdioType, ExecInfo, ExitStatus,
 };
 pub use crate::credentials::{Credential, UserPassword};
-use crate::errors::{DockerError, Error as DwError};
+use crate::errors::{DockerError, DockerErrorKind, Error as DwError};
 use crate::event::EventResponse;
 use crate::filesystem::{FilesystemChange, XDockerContainerPathStat};
 use crate::http_client::{HaveHttpClient, HttpClient};
 use crate::hyper_client::HyperClient;
-use crate::image::{FoundImage, Image, ImageFilters, ImageId, SummaryImage};
+use crate::image::{FoundImage, Image, ImageFilters, ImageId, ListImageFilters, SummaryImage};
 use crate::network::*;
 use crate::options::*;
 use crate::process::{Process, Top};
-use crate::response::Response as DockerResponse;
+use crate::response::{PullProgress, Response as DockerResponse};
 use crate::signal::Signal;
-use crate::stats::Stats;
-use crate::system::{AuthToken, SystemInfo};
+use crate::stats::{Stats, StatsSummary};
+use crate::system::{AuthToken, LocalNodeState, SystemInfo};
 use crate::version::Version;
+use crate::volume::*;
 use base64::{engine::general_purpose, Engine as _};
 use bytes::Bytes;
 #[cfg(feature = "experimental")]
 use checkpoint::{Checkpoint, CheckpointCreateOptions, CheckpointDeleteOptions};
+use chrono::{DateTime, FixedOffset};
 use futures::stream::BoxStream;
-use http::{HeaderMap, StatusCode};
+use http::{HeaderMap, HeaderValue, StatusCode};
 use log::debug;
 use serde::de::DeserializeOwned;
+use std::collections::HashMap;
 use std::env;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::time::Duration;
+use tokio::sync::broadcast;
 
-async fn into_aframe_stream(
+pub(crate) async fn into_aframe_stream(
     body: hyper::Body,
 ) -> Result<BoxStream<'static, Result<AttachResponseFrame, DwError>>, DwError> {
-    use futures::stream::StreamExt;
     use futures::stream::TryStreamExt;
-    let mut aread = tokio_util::io::StreamReader::new(
+    let aread = tokio_util::io::StreamReader::new(
         body.map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err)),
     );
+    Ok(read_aframes(aread))
+}
+
+/// Read frames of the attach protocol from `aread`
+fn read_aframes<R>(mut aread: R) -> BoxStream<'static, Result<AttachResponseFrame, DwError>>
+where
+    R: tokio::io::AsyncRead + Unpin + Send + 'static,
+{
+    use futures::stream::StreamExt;
     let mut buf = [0u8; 8];
     let src = async_stream::stream! {
         loop {
@@ -81,12 +94,19 @@ async fn into_aframe_stream(
             }
         }
     };
-    Ok(src.boxed())
+    src.boxed()
+}
+
+async fn into_docker_error(res: http::Response<hyper::Body>) -> Result<DockerError, DwError> {
+    let status = res.status();
+    let body = hyper::body::to_bytes(res.into_body()).await?;
+    docker_error(status, body.as_ref())
 }
 
-async fn into_docker_error(body: hyper::Body) -> Result<DockerError, DwError> {
-    let body = hyper::body::to_bytes(body).await?;
-    let err = serde_json::from_slice::<DockerError>(body.as_ref())?;
+/// Deserialize an error response with the status code
+fn docker_error(status: StatusCode, body: &[u8]) -> Result<DockerError, DwError> {
+    let mut err = serde_json::from_slice::<DockerError>(body)?;
+    err.status = Some(status);
     Ok(err)
 }
 
@@ -102,6 +122,138 @@ fn into_lines(body: hyper::Body) -> Result<BoxStream<'static, Result<String, DwE
     Ok(stream)
 }
 
+/// Split log lines from a logs stream
+///
+/// Output of a container without tty is multiplexed into stdout/stderr frames.
+/// Lines which are not valid UTF-8 are converted lossily.
+async fn into_log_lines(
+    body: hyper::Body,
+    tty: bool,
+) -> Result<BoxStream<'static, Result<String, DwError>>, DwError> {
+    use futures::stream::StreamExt;
+    use futures::stream::TryStreamExt;
+    use tokio::io::AsyncBufReadExt;
+    let chunks: BoxStream<'static, Result<Bytes, DwError>> = if tty {
+        body.map_err(Into::into).boxed()
+    } else {
+        into_aframe_stream(body)
+            .await?
+            .map_ok(|frame| Bytes::from(frame.frame))
+            .boxed()
+    };
+    let aread = tokio_util::io::StreamReader::new(
+        chunks.map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err)),
+    );
+    let stream = tokio_stream::wrappers::SplitStream::new(aread.split(b'\n')).map(|line| {
+        let mut line = line?;
+        if line.last() == Some(&b'\r') {
+            line.pop();
+        }
+        Ok(String::from_utf8_lossy(&line).into_owned())
+    });
+    Ok(stream.boxed())
+}
+
+/// Read from `src` until `buf` holds at least `len` bytes
+///
+/// Returns false if `src` ends before that.
+async fn fill_buf(
+    src: &mut BoxStream<'static, Result<Bytes, DwError>>,
+    buf: &mut bytes::BytesMut,
+    len: usize,
+) -> Result<bool, DwError> {
+    use futures::stream::StreamExt;
+    while buf.len() < len {
+        match src.next().await {
+            Some(chunk) => buf.extend_from_slice(&chunk?),
+            None => return Ok(false),
+        }
+    }
+    Ok(true)
+}
+
+fn tar_path(bytes: &[u8]) -> Result<PathBuf, DwError> {
+    let bytes = bytes.split(|&b| b == 0).next().unwrap_or_default();
+    std::str::from_utf8(bytes)
+        .map(PathBuf::from)
+        .map_err(|err| DwError::Unknown {
+            message: format!("invalid path in tar archive: {err}"),
+        })
+}
+
+/// Extract regular files from a tar stream without buffering the whole archive
+fn into_tar_files(
+    mut src: BoxStream<'static, Result<Bytes, DwError>>,
+) -> BoxStream<'static, Result<(PathBuf, Bytes), DwError>> {
+    use futures::stream::StreamExt;
+    const BLOCK_SIZE: usize = 512;
+    let stream = async_stream::try_stream! {
+        let mut buf = bytes::BytesMut::new();
+        // path given by a preceding GNU long name or pax header
+        let mut long_path = None;
+        loop {
+            if !fill_buf(&mut src, &mut buf, BLOCK_SIZE).await? {
+                if buf.is_empty() {
+                    break;
+                }
+                Err(DwError::Unknown {
+                    message: "unexpected end of tar archive".to_owned(),
+                })?;
+            }
+            let block = buf.split_to(BLOCK_SIZE);
+            if block.iter().all(|&b| b == 0) {
+                break; // end of archive
+            }
+            let header = tar::Header::from_byte_slice(&block);
+            // the size is read from the archive, so do not trust it
+            let size = header.entry_size()?;
+            let padded = usize::try_from(size)
+                .ok()
+                .and_then(|size| size.checked_add(BLOCK_SIZE - 1))
+                .map(|size| size / BLOCK_SIZE * BLOCK_SIZE)
+                .ok_or_else(|| DwError::Unknown {
+                    message: format!("invalid entry size in tar archive: {size}"),
+                })?;
+            let size = size as usize;
+            if !fill_buf(&mut src, &mut buf, padded).await? {
+                Err(DwError::Unknown {
+                    message: "unexpected end of tar archive".to_owned(),
+                })?;
+            }
+            let data = buf.split_to(padded).freeze().slice(..size);
+            let entry_type = header.entry_type();
+            if entry_type.is_gnu_longname() {
+                long_path = Some(tar_path(&data)?);
+            } else if entry_type.is_pax_local_extensions() {
+                for ext in tar::PaxExtensions::new(&data) {
+                    let ext = ext?;
+                    if ext.key() == Ok("path") {
+                        long_path = Some(tar_path(ext.value_bytes())?);
+                    }
+                }
+            } else {
+                let path = match long_path.take() {
+                    Some(path) => path,
+                    None => tar_path(&header.path_bytes())?,
+                };
+                if entry_type.is_file() || entry_type.is_contiguous() {
+                    yield (path, data);
+                }
+            }
+        }
+    };
+    stream.boxed()
+}
+
+/// Split a log line into the leading timestamp and the message
+fn split_log_timestamp(line: &str) -> Result<(DateTime<FixedOffset>, String), DwError> {
+    let (timestamp, message) = line.split_once(' ').unwrap_or((line, ""));
+    let timestamp = DateTime::parse_from_rfc3339(timestamp).map_err(|err| DwError::Unknown {
+        message: format!("invalid timestamp of a log line: {line:?}: {err}"),
+    })?;
+    Ok((timestamp, message.to_owned()))
+}
+
 pub fn into_jsonlines<T>(
     body: hyper::Body,
 ) -> Result<BoxStream<'static, Result<T, DwError>>, DwError>
@@ -119,6 +271,166 @@ where
     Ok(stream)
 }
 
+/// Normalize a repository name of the default registry, e.g. `docker.io/library/alpine` to `alpine`
+fn normalize_repository(repository: &str) -> &str {
+    let repository = repository
+        .strip_prefix("docker.io/")
+        .or_else(|| repository.strip_prefix("index.docker.io/"))
+        .unwrap_or(repository);
+    repository.strip_prefix("library/").unwrap_or(repository)
+}
+
+/// `repo:tag` of the images, excluding dangling `<none>:<none>` entries
+pub(crate) fn repo_tags(images: &[SummaryImage]) -> Vec<String> {
+    let mut tags: Vec<String> = images
+        .iter()
+        .flat_map(|image| image.RepoTags.iter())
+        .filter(|tag| tag.as_str() != "<none>:<none>")
+        .cloned()
+        .collect();
+    tags.sort();
+    tags.dedup();
+    tags
+}
+
+/// Dockerfile instruction to set the label `key` to `value`
+fn label_change(key: &str, value: &str) -> String {
+    fn quote(s: &str) -> String {
+        format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+    format!("LABEL {}={}", quote(key), quote(value))
+}
+
+/// Find the digest of the repository `image` in `RepoDigests` of an image
+fn repo_digest_of<'a>(repo_digests: &'a [String], image: &str) -> Option<&'a str> {
+    let image = normalize_repository(image);
+    repo_digests
+        .iter()
+        .map(String::as_str)
+        .find(|digest| match digest.split_once('@') {
+            Some((repository, _)) => normalize_repository(repository) == image,
+            None => false,
+        })
+}
+
+/// maximum number of requests in flight in methods fanning out requests
+const CONCURRENT_REQUESTS: usize = 8;
+
+/// interval before reconnecting in `events_resilient` and `stats_resilient`
+const RECONNECT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Whether the error is caused by a lost or refused connection to the daemon
+fn is_connection_error(err: &DwError) -> bool {
+    use std::io::ErrorKind;
+    let mut source: Option<&(dyn std::error::Error + 'static)> = Some(err);
+    while let Some(err) = source {
+        if let Some(err) = err.downcast_ref::<std::io::Error>() {
+            if matches!(
+                err.kind(),
+                ErrorKind::ConnectionReset
+                    | ErrorKind::ConnectionRefused
+                    | ErrorKind::ConnectionAborted
+                    | ErrorKind::BrokenPipe
+                    | ErrorKind::UnexpectedEof
+            ) {
+                return true;
+            }
+            // `source` of io::Error skips the wrapped error itself
+            source = err
+                .get_ref()
+                .map(|err| err as &(dyn std::error::Error + 'static));
+            continue;
+        }
+        if let Some(err) = err.downcast_ref::<hyper::Error>() {
+            if err.is_closed() || err.is_incomplete_message() {
+                return true;
+            }
+        }
+        source = err.source();
+    }
+    false
+}
+
+/// Whether the error may be resolved by waiting for the daemon to start,
+/// i.e. the connection is refused or the unix socket does not exist yet
+fn is_daemon_starting(err: &DwError) -> bool {
+    is_connection_error(err)
+        || std::iter::successors(Some(err as &(dyn std::error::Error + 'static)), |err| {
+            err.source()
+        })
+        .filter_map(|err| err.downcast_ref::<std::io::Error>())
+        .any(|err| err.kind() == std::io::ErrorKind::NotFound)
+}
+
+/// first interval of retrying in `wait_until_ready`, doubled up to `RECONNECT_INTERVAL`
+const READY_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// interval of polling in `wait_container_removed`
+const REMOVAL_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Query parameters of the attach api
+///
+/// `detach_keys` is validated as [`DetachKeys`].
+fn attach_params(
+    detach_keys: Option<&str>,
+    logs: bool,
+    stream: bool,
+    stdin: bool,
+    stdout: bool,
+    stderr: bool,
+) -> Result<String, DwError> {
+    let mut param = url::form_urlencoded::Serializer::new(String::new());
+    if let Some(keys) = detach_keys {
+        keys.parse::<DetachKeys>()
+            .map_err(|reason| DwError::InvalidDetachKeys {
+                keys: keys.to_owned(),
+                reason,
+            })?;
+        param.append_pair("detachKeys", keys);
+    }
+    param.append_pair("logs", &logs.to_string());
+    param.append_pair("stream", &stream.to_string());
+    param.append_pair("stdin", &stdin.to_string());
+    param.append_pair("stdout", &stdout.to_string());
+    param.append_pair("stderr", &stderr.to_string());
+    Ok(param.finish())
+}
+
+/// Container events which change the state of the container
+///
+/// "stop", "kill" and "oom" are left out: they do not change the state by themselves,
+/// and "die" follows them if the container actually exits.
+const CONTAINER_STATE_EVENTS: &[&str] = &[
+    "create", "start", "restart", "pause", "unpause", "die", "destroy",
+];
+
+/// Status of a container after the event `action` occurred
+///
+/// "destroy" has no status, since the container no longer exists.
+fn container_status_of_event(action: &str) -> Option<ContainerStatus> {
+    match action {
+        "create" => Some(ContainerStatus::Created),
+        "start" | "restart" | "unpause" => Some(ContainerStatus::Running),
+        "pause" => Some(ContainerStatus::Paused),
+        "die" => Some(ContainerStatus::Exited),
+        _ => None,
+    }
+}
+
+/// Split an image reference into the image name and the tag (or digest)
+///
+/// The tag defaults to `latest`.
+fn split_image_reference(image: &str) -> (&str, &str) {
+    if let Some((name, digest)) = image.split_once('@') {
+        return (name, digest);
+    }
+    let name_start = image.rfind('/').map(|i| i + 1).unwrap_or(0);
+    match image[name_start..].rfind(':') {
+        Some(i) => (&image[..name_start + i], &image[name_start + i + 1..]),
+        None => (image, "latest"),
+    }
+}
+
 /// The default `DOCKER_HOST` address that we will try to connect to.
 #[cfg(unix)]
 pub static DEFAULT_DOCKER_HOST: &str = "unix:///var/run/docker.sock";
@@ -152,6 +464,189 @@ enum Protocol {
     Tcp,
 }
 
+/// Stdin of a container attached by `Docker::attach_container_with_stdin`
+///
+/// Writes are sent to the container as they are.
+/// `close_stdin` closes only the write half of the connection,
+/// so that the output of the container can still be read.
+#[derive(Debug)]
+pub struct AttachStdin {
+    stdin: tokio::io::WriteHalf<hyper::upgrade::Upgraded>,
+}
+
+impl AttachStdin {
+    /// Write all of `buf` to the stdin of the container
+    pub async fn write_all(&mut self, buf: &[u8]) -> Result<(), DwError> {
+        use tokio::io::AsyncWriteExt;
+        self.stdin.write_all(buf).await?;
+        self.stdin.flush().await?;
+        Ok(())
+    }
+
+    /// Signal EOF to the stdin of the container
+    ///
+    /// The daemon closes the stdin of the container
+    /// if it is created with `stdin_once` and without a tty.
+    pub async fn close_stdin(&mut self) -> Result<(), DwError> {
+        use tokio::io::AsyncWriteExt;
+        self.stdin.shutdown().await?;
+        Ok(())
+    }
+}
+
+impl tokio::io::AsyncWrite for AttachStdin {
+    fn poll_write(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+        buf: &[u8],
+    ) -> std::task::Poll<std::io::Result<usize>> {
+        std::pin::Pin::new(&mut self.stdin).poll_write(cx, buf)
+    }
+
+    fn poll_flush(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        std::pin::Pin::new(&mut self.stdin).poll_flush(cx)
+    }
+
+    fn poll_shutdown(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<std::io::Result<()>> {
+        std::pin::Pin::new(&mut self.stdin).poll_shutdown(cx)
+    }
+}
+
+/// Output of a container shared by `Docker::attach_container_broadcast`
+///
+/// Each subscription receives the frames sent after it is subscribed.
+/// The container is never blocked by slow subscribers: a subscription which falls
+/// `capacity` frames behind skips the oldest ones and receives `DwError::Lagged`.
+/// The container stays attached while this handle or any subscription is alive.
+#[derive(Debug)]
+pub struct AttachBroadcast {
+    receiver: broadcast::Receiver<Result<AttachResponseFrame, Arc<DwError>>>,
+}
+
+impl AttachBroadcast {
+    /// Subscribe to the frames sent from now on
+    pub fn subscribe(&self) -> BoxStream<'static, Result<AttachResponseFrame, DwError>> {
+        subscribe_aframes(self.receiver.resubscribe())
+    }
+}
+
+impl Clone for AttachBroadcast {
+    fn clone(&self) -> Self {
+        Self {
+            receiver: self.receiver.resubscribe(),
+        }
+    }
+}
+
+fn subscribe_aframes(
+    mut receiver: broadcast::Receiver<Result<AttachResponseFrame, Arc<DwError>>>,
+) -> BoxStream<'static, Result<AttachResponseFrame, DwError>> {
+    let stream = async_stream::stream! {
+        loop {
+            match receiver.recv().await {
+                Ok(Ok(frame)) => yield Ok(frame),
+                Ok(Err(err)) => yield Err(DwError::Broadcast(err)),
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    yield Err(DwError::Lagged { skipped })
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    };
+    Box::pin(stream)
+}
+
+/// Container removed (forcibly) when the guard is dropped
+///
+/// Created by `Docker::create_container_guarded`.
+/// Call `remove` to remove the container reliably; removal on drop is only best-effort.
+/// Dropping the guard within a tokio runtime spawns the removal on that runtime without waiting
+/// for it, so the removal is asynchronous and the container may still exist right after the drop
+/// (use `Docker::wait_container_removed` to wait for it). Outside of a runtime, it blocks the
+/// current thread until the container is removed.
+#[derive(Debug)]
+pub struct ContainerGuard {
+    docker: Docker,
+    /// `None` after the container is removed or kept
+    id: Option<String>,
+}
+
+impl ContainerGuard {
+    /// Id of the container
+    pub fn id(&self) -> &str {
+        self.id.as_deref().unwrap_or_default()
+    }
+
+    /// Remove the container now
+    ///
+    /// Unlike dropping the guard, this waits for the removal and reports its failure,
+    /// so use this whenever the guard can be consumed in an async context.
+    pub async fn remove(mut self) -> Result<(), DwError> {
+        match self.id.take() {
+            Some(id) => {
+                self.docker
+                    .remove_container(&id, None, Some(true), None)
+                    .await
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Keep the container and return its id
+    pub fn keep(mut self) -> String {
+        self.id.take().unwrap_or_default()
+    }
+}
+
+/// Best-effort removal of the container
+///
+/// Within a tokio runtime, the removal is a spawned task which nothing waits for.
+/// It is cancelled if the runtime shuts down first, e.g. when the guard is dropped at the end of
+/// `#[tokio::main]` or `#[tokio::test]`, and then the container is left behind.
+/// Errors are only logged.
+impl Drop for ContainerGuard {
+    fn drop(&mut self) {
+        let id = match self.id.take() {
+            Some(id) => id,
+            None => return,
+        };
+        async fn remove(docker: Docker, id: String) {
+            match docker.remove_container(&id, None, Some(true), None).await {
+                Ok(()) => {}
+                Err(DwError::Docker(err)) if err.kind() == DockerErrorKind::NotFound => {}
+                Err(err) => log::warn!("failed to remove a guarded container: {err}"),
+            }
+        }
+        if let Ok(handle) = tokio::runtime::Handle::try_current() {
+            handle.spawn(remove(self.docker.clone(), id));
+            return;
+        }
+        // connections opened on the temporary runtime die with it, so do not share the pool
+        let docker = Docker {
+            client: self.docker.client.unpooled(),
+            ..self.docker.clone()
+        };
+        let remove = std::thread::spawn(move || -> Result<(), DwError> {
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()?;
+            runtime.block_on(remove(docker, id));
+            Ok(())
+        });
+        match remove.join() {
+            Ok(Ok(())) => {}
+            Ok(Err(err)) => log::warn!("failed to remove a guarded container: {err}"),
+            Err(_) => log::error!("panicked while removing a guarded container"),
+        }
+    }
+}
+
 /// Handle to connection to the docker daemon
 #[derive(Debug, Clone)]
 pub struct Docker {
@@ -171,16 +666,29 @@ fn api_result<D: DeserializeOwned>(res: http::Response<Vec<u8>>) -> Result<D, Dw
     if res.status().is_success() {
         Ok(serde_json::from_slice::<D>(res.body())?)
     } else {
-        Err(serde_json::from_slice::<DockerError>(res.body())?.into())
+        Err(docker_error(res.status(), res.body())?.into())
     }
 }
 
+/// Encode a credential into the value of `X-Registry-Auth` header
+///
+/// The header value is a base64 encoded JSON of the credential.
+fn registry_auth_header(credential: &Credential) -> Result<HeaderValue, DwError> {
+    let json = serde_json::to_string(credential)?;
+    general_purpose::STANDARD
+        .encode(json.as_bytes())
+        .parse()
+        .map_err(|err| DwError::Unknown {
+            message: format!("invalid X-Registry-Auth header: {err}"),
+        })
+}
+
 /// Expect 204 NoContent
 fn no_content(res: http::Response<Vec<u8>>) -> Result<(), DwError> {
     if res.status() == StatusCode::NO_CONTENT {
         Ok(())
     } else {
-        Err(serde_json::from_slice::<DockerError>(res.body())?.into())
+        Err(docker_error(res.status(), res.body())?.into())
     }
 }
 
@@ -189,7 +697,7 @@ fn no_content_or_not_modified(res: http::Response<Vec<u8>>) -> Result<(), DwErro
     if res.status() == StatusCode::NO_CONTENT || res.status() == StatusCode::NOT_MODIFIED {
         Ok(())
     } else {
-        Err(serde_json::from_slice::<DockerError>(res.body())?.into())
+        Err(docker_error(res.status(), res.body())?.into())
     }
 }
 
@@ -200,8 +708,93 @@ fn ignore_result(res: http::Response<Vec<u8>>) -> Result<(), DwError> {
     if res.status().is_success() {
         Ok(())
     } else {
-        Err(serde_json::from_slice::<DockerError>(res.body())?.into())
+        Err(docker_error(res.status(), res.body())?.into())
+    }
+}
+
+/// path of the archive api for putting files into `dst` of the container `id`
+#[allow(non_snake_case)]
+fn archive_path(id: &str, dst: &Path, noOverwriteDirNonDir: bool) -> String {
+    let mut param = url::form_urlencoded::Serializer::new(String::new());
+    param.append_pair("path", &dst.to_string_lossy());
+    param.append_pair("noOverwriteDirNonDir", &noOverwriteDirNonDir.to_string());
+    format!("/containers/{}/archive?{}", id, param.finish())
+}
+
+/// stream the file at `path` as a request body, reporting the cumulative bytes read
+async fn file_body_with_progress(
+    path: &Path,
+    mut progress: impl FnMut(u64) + Send + 'static,
+) -> Result<hyper::Body, DwError> {
+    use futures::stream::TryStreamExt;
+    let file = tokio::fs::File::open(path).await?;
+    let mut sent = 0u64;
+    let stream = tokio_util::io::ReaderStream::new(file).map_ok(move |chunk| {
+        sent += chunk.len() as u64;
+        progress(sent);
+        chunk
+    });
+    Ok(hyper::Body::wrap_stream(stream))
+}
+
+/// find the image id from the XXXXXX.json entry of an image archive
+async fn image_id_in_archive(path: &Path) -> Result<ImageId, DwError> {
+    let path = path.to_owned();
+    tokio::task::spawn_blocking(move || {
+        let file = std::fs::File::open(path)?;
+        let mut ar = tar::Archive::new(file);
+        for entry in ar.entries()?.filter_map(|e| e.ok()) {
+            let path = entry.path()?;
+            // looking for file name like XXXXXXXXXXXXXX.json
+            if path.extension() == Some(std::ffi::OsStr::new("json"))
+                && path != Path::new("manifest.json")
+            {
+                let stem = path.file_stem().unwrap(); // contains .json
+                let id = stem.to_str().ok_or(DwError::Unknown {
+                    message: format!("convert to String: {stem:?}"),
+                })?;
+                return Ok(ImageId::new(id.to_string()));
+            }
+        }
+        Err(DwError::Unknown {
+            message: "no expected file: XXXXXX.json".to_owned(),
+        })
+    })
+    .await
+    .expect("join error")
+}
+
+/// Extract a tar archive into `dest`, rejecting entries escaping from `dest`
+///
+/// Device files and fifos are skipped.
+fn unpack_archive<R: std::io::Read>(archive: R, dest: &Path) -> Result<(), DwError> {
+    use std::path::Component;
+    std::fs::create_dir_all(dest)?;
+    let mut archive = tar::Archive::new(archive);
+    archive.set_preserve_permissions(true);
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let path = entry.path()?.into_owned();
+        if path
+            .components()
+            .any(|component| matches!(component, Component::ParentDir | Component::Prefix(_)))
+        {
+            return Err(DwError::Unknown {
+                message: format!("unsafe path in archive: {}", path.display()),
+            });
+        }
+        let entry_type = entry.header().entry_type();
+        if entry_type.is_character_special()
+            || entry_type.is_block_special()
+            || entry_type.is_fifo()
+        {
+            debug!("skip special file: {}", path.display());
+            continue;
+        }
+        // also refuses to write through symbolic links pointing outside of `dest`
+        entry.unpack_in(dest)?;
     }
+    Ok(())
 }
 
 impl Docker {
@@ -223,6 +816,32 @@ impl Docker {
         &self.headers
     }
 
+    /// Headers with `X-Registry-Auth` of the stored credential, if any
+    ///
+    /// The credential is copied out, so that the lock is never held across await points.
+    fn headers_with_registry_auth(&self) -> Result<HeaderMap, DwError> {
+        let credential = self.credential.lock().unwrap().clone();
+        let mut headers = self.headers().clone();
+        if let Some(credential) = credential {
+            headers.insert("X-Registry-Auth", registry_auth_header(&credential)?);
+        }
+        Ok(headers)
+    }
+
+    /// Send a raw request, adding the headers of this client
+    #[cfg(feature = "tower")]
+    pub(crate) async fn send_request(
+        &self,
+        mut request: http::Request<hyper::Body>,
+    ) -> Result<http::Response<hyper::Body>, DwError> {
+        for (name, value) in self.headers() {
+            if !request.headers().contains_key(name) {
+                request.headers_mut().insert(name, value.clone());
+            }
+        }
+        self.client.send(request).await
+    }
+
     /// Connect to the Docker daemon
     ///
     /// # Summary
@@ -319,6 +938,20 @@ impl Docker {
         Ok(Docker::new(client, Protocol::Tcp))
     }
 
+    /// Clone this client with a deadline of every request made through it
+    ///
+    /// # Summary
+    /// Requests which do not complete by `deadline` fail with `DwError::Timeout`.
+    /// For streaming responses, such as `export_image` or `stats`,
+    /// reading the body after the deadline fails as well.
+    /// The original client is not affected.
+    pub fn with_deadline(&self, deadline: tokio::time::Instant) -> Docker {
+        Docker {
+            client: self.client.with_deadline(deadline),
+            ..self.clone()
+        }
+    }
+
     /// List containers
     ///
     /// # API
@@ -348,6 +981,24 @@ impl Docker {
         api_result(res).map_err(Into::into)
     }
 
+    /// List running containers, like `docker ps`
+    ///
+    /// # API
+    /// /containers/json
+    pub async fn running_containers(&self) -> Result<Vec<Container>, DwError> {
+        self.list_containers(None, None, None, ContainerFilters::default())
+            .await
+    }
+
+    /// List all containers including stopped ones, like `docker ps -a`
+    ///
+    /// # API
+    /// /containers/json
+    pub async fn all_containers(&self) -> Result<Vec<Container>, DwError> {
+        self.list_containers(Some(true), None, None, ContainerFilters::default())
+            .await
+    }
+
     /// Create a container
     ///
     /// # Summary
@@ -355,6 +1006,8 @@ impl Docker {
     /// * `name` - None: auto naming
     /// * `option` - create options
     ///
+    /// Warnings of the daemon are logged; see `CreateContainerResponse::warnings`.
+    ///
     /// # API
     /// POST /containers/create?{name}
     pub async fn create_container(
@@ -362,13 +1015,94 @@ impl Docker {
         name: Option<&str>,
         option: &ContainerCreateOptions,
     ) -> Result<CreateContainerResponse, DwError> {
-        let path = match name {
-            Some(name) => {
-                let mut param = url::form_urlencoded::Serializer::new(String::new());
-                param.append_pair("name", name);
-                format!("/containers/create?{}", param.finish())
-            }
-            None => "/containers/create".to_string(),
+        let mut params = CreateParams::new();
+        if let Some(name) = name {
+            params.name(name);
+        }
+        self.create_container_with(&params, option).await
+    }
+
+    /// Create a container with the query parameters `params`
+    ///
+    /// # Summary
+    /// If `params.pull` is set and the daemon reports that the image is missing,
+    /// the image is pulled (for `params.platform`, with the stored credential)
+    /// and then creating is retried once.
+    ///
+    /// Warnings of the daemon are logged; see `CreateContainerResponse::warnings`.
+    ///
+    /// # API
+    /// POST /containers/create?{name}&{platform}
+    /// POST /images/create?fromImage={image}&tag={tag}&{platform}
+    pub async fn create_container_with(
+        &self,
+        params: &CreateParams,
+        option: &ContainerCreateOptions,
+    ) -> Result<CreateContainerResponse, DwError> {
+        let query = params.to_url_params();
+        let mut res = self.post_create_container(&query, option).await?;
+        if params.pull && res.status() == StatusCode::NOT_FOUND {
+            let (image, tag) = split_image_reference(&option.image);
+            debug!("image {image}:{tag} is not found. pulling...");
+            self.pull_image(image, tag, params.platform.as_deref())
+                .await?;
+            res = self.post_create_container(&query, option).await?;
+        }
+        let res: CreateContainerResponse = api_result(res)?;
+        for warning in res.warnings() {
+            log::warn!("creating container {}: {}", res.id, warning);
+        }
+        Ok(res)
+    }
+
+    /// Create a container removed when the returned guard is dropped
+    ///
+    /// # API
+    /// POST /containers/create?{name}
+    pub async fn create_container_guarded(
+        &self,
+        name: Option<&str>,
+        option: &ContainerCreateOptions,
+    ) -> Result<ContainerGuard, DwError> {
+        let res = self.create_container(name, option).await?;
+        Ok(ContainerGuard {
+            docker: self.clone(),
+            id: Some(res.id),
+        })
+    }
+
+    /// Create a container, pulling the image if it does not exist
+    ///
+    /// # Summary
+    /// If the daemon reports that the image is missing,
+    /// the image is pulled (with the stored credential) and then creating is retried once.
+    /// This is similar to the implicit pull of `docker run`.
+    ///
+    /// # API
+    /// POST /containers/create?{name}
+    /// POST /images/create?fromImage={image}&tag={tag}
+    pub async fn create_container_pulling(
+        &self,
+        name: Option<&str>,
+        option: &ContainerCreateOptions,
+    ) -> Result<CreateContainerResponse, DwError> {
+        let mut params = CreateParams::new();
+        params.pull(true);
+        if let Some(name) = name {
+            params.name(name);
+        }
+        self.create_container_with(&params, option).await
+    }
+
+    async fn post_create_container(
+        &self,
+        query: &str,
+        option: &ContainerCreateOptions,
+    ) -> Result<http::Response<Vec<u8>>, DwError> {
+        let path = if query.is_empty() {
+            "/containers/create".to_string()
+        } else {
+            format!("/containers/create?{query}")
         };
 
         let json_body = serde_json::to_string(&option)?;
@@ -377,8 +1111,7 @@ impl Docker {
             http::header::CONTENT_TYPE,
             "application/json".parse().unwrap(),
         );
-        let res = self.http_client().post(&headers, &path, &json_body).await?;
-        api_result(res).map_err(Into::into)
+        self.http_client().post(&headers, &path, &json_body).await
     }
 
     /// Start a container
@@ -447,9 +1180,22 @@ impl Docker {
     /// # API
     /// /containers/{id}/kill
     pub async fn kill_container(&self, id: &str, signal: Signal) -> Result<(), DwError> {
+        self.kill_container_named(id, &signal.as_i32().to_string())
+            .await
+    }
+
+    /// Kill a container with a signal name like `SIGTERM`
+    ///
+    /// # Summary
+    /// `signal` is passed to the daemon as it is,
+    /// which accepts signal names (with or without `SIG`) as well as numbers.
+    ///
+    /// # API
+    /// /containers/{id}/kill
+    pub async fn kill_container_named(&self, id: &str, signal: &str) -> Result<(), DwError> {
         let param = {
             let mut param = url::form_urlencoded::Serializer::new(String::new());
-            param.append_pair("signal", &signal.as_i32().to_string());
+            param.append_pair("signal", signal);
             param.finish()
         };
         let res = self
@@ -484,15 +1230,89 @@ impl Docker {
         no_content(res).map_err(Into::into)
     }
 
-    /// Attach to a container
+    /// Pause a container
     ///
-    /// Attach to a container to read its output or send it input.
+    /// # Summary
+    /// All processes in the container are suspended by the cgroup freezer.
     ///
     /// # API
-    /// /containers/{id}/attach
-    #[allow(non_snake_case)]
-    #[allow(clippy::too_many_arguments)]
-    pub async fn attach_container(
+    /// /containers/{id}/pause
+    pub async fn pause_container(&self, id: &str) -> Result<(), DwError> {
+        let res = self
+            .http_client()
+            .post(self.headers(), &format!("/containers/{id}/pause"), "")
+            .await?;
+        no_content(res)
+    }
+
+    /// Unpause a container
+    ///
+    /// # API
+    /// /containers/{id}/unpause
+    pub async fn unpause_container(&self, id: &str) -> Result<(), DwError> {
+        let res = self
+            .http_client()
+            .post(self.headers(), &format!("/containers/{id}/unpause"), "")
+            .await?;
+        no_content(res)
+    }
+
+    /// Update configurations of a container
+    ///
+    /// # API
+    /// /containers/{id}/update
+    pub async fn update_container(
+        &self,
+        id: &str,
+        option: &ContainerUpdateOptions,
+    ) -> Result<UpdateContainerResponse, DwError> {
+        let json_body = serde_json::to_string(&option)?;
+        let mut headers = self.headers().clone();
+        headers.insert(
+            http::header::CONTENT_TYPE,
+            "application/json".parse().unwrap(),
+        );
+        let res = self
+            .http_client()
+            .post(&headers, &format!("/containers/{id}/update"), &json_body)
+            .await?;
+        api_result(res)
+    }
+
+    /// Rename a container
+    ///
+    /// If `name` is already taken, the returned `DockerError` is classified as
+    /// `DockerErrorKind::Conflict`.
+    ///
+    /// # API
+    /// /containers/{id}/rename
+    pub async fn rename_container(&self, id: &str, name: &str) -> Result<(), DwError> {
+        let param = {
+            let mut param = url::form_urlencoded::Serializer::new(String::new());
+            param.append_pair("name", name);
+            param.finish()
+        };
+        let res = self
+            .http_client()
+            .post(
+                self.headers(),
+                &format!("/containers/{}/rename?{}", id, param),
+                "",
+            )
+            .await?;
+        no_content(res)
+    }
+
+    /// Attach to a container
+    ///
+    /// Attach to a container to read its output or send it input.
+    /// `detachKeys` is validated as [`DetachKeys`] before the request is sent.
+    ///
+    /// # API
+    /// /containers/{id}/attach
+    #[allow(non_snake_case)]
+    #[allow(clippy::too_many_arguments)]
+    pub async fn attach_container(
         &self,
         id: &str,
         detachKeys: Option<&str>,
@@ -502,18 +1322,7 @@ impl Docker {
         stdout: bool,
         stderr: bool,
     ) -> Result<BoxStream<'static, Result<AttachResponseFrame, DwError>>, DwError> {
-        let param = {
-            let mut param = url::form_urlencoded::Serializer::new(String::new());
-            if let Some(keys) = detachKeys {
-                param.append_pair("detachKeys", keys);
-            }
-            param.append_pair("logs", &logs.to_string());
-            param.append_pair("stream", &stream.to_string());
-            param.append_pair("stdin", &stdin.to_string());
-            param.append_pair("stdout", &stdout.to_string());
-            param.append_pair("stderr", &stderr.to_string());
-            param.finish()
-        };
+        let param = attach_params(detachKeys, logs, stream, stdin, stdout, stderr)?;
         let res = self
             .http_client()
             .post_stream(
@@ -525,8 +1334,98 @@ impl Docker {
         if res.status().is_success() {
             into_aframe_stream(res.into_body()).await
         } else {
-            Err(into_docker_error(res.into_body()).await?.into())
+            Err(into_docker_error(res).await?.into())
+        }
+    }
+
+    /// Attach to a container to share its output among multiple consumers
+    ///
+    /// # Summary
+    /// Frames of stdout and stderr are forwarded by a spawned task,
+    /// so this must be called within a tokio runtime.
+    /// Returns the first subscription and a handle to subscribe more.
+    /// Each subscription buffers up to `capacity` (at least 1) frames; see [`AttachBroadcast`].
+    /// `detach_keys` is validated as [`DetachKeys`] before the request is sent.
+    ///
+    /// # API
+    /// /containers/{id}/attach
+    pub async fn attach_container_broadcast(
+        &self,
+        id: &str,
+        detach_keys: Option<&str>,
+        logs: bool,
+        capacity: usize,
+    ) -> Result<
+        (
+            BoxStream<'static, Result<AttachResponseFrame, DwError>>,
+            AttachBroadcast,
+        ),
+        DwError,
+    > {
+        use futures::stream::StreamExt;
+        let mut frames = self
+            .attach_container(id, detach_keys, logs, true, false, true, true)
+            .await?;
+        let (sender, receiver) = broadcast::channel(capacity.max(1));
+        tokio::spawn(async move {
+            while let Some(frame) = frames.next().await {
+                // stop attaching when all subscriptions are dropped
+                if sender.send(frame.map_err(Arc::new)).is_err() {
+                    break;
+                }
+            }
+        });
+        let broadcast = AttachBroadcast { receiver };
+        Ok((broadcast.subscribe(), broadcast))
+    }
+
+    /// Attach to a container to write its stdin and read its output
+    ///
+    /// # Summary
+    /// The connection is hijacked from the daemon,
+    /// and split into the stdin of the container and a stream of its stdout and stderr.
+    /// `detach_keys` is validated as [`DetachKeys`] before the request is sent.
+    ///
+    /// # API
+    /// /containers/{id}/attach
+    pub async fn attach_container_with_stdin(
+        &self,
+        id: &str,
+        detach_keys: Option<&str>,
+        logs: bool,
+    ) -> Result<
+        (
+            AttachStdin,
+            BoxStream<'static, Result<AttachResponseFrame, DwError>>,
+        ),
+        DwError,
+    > {
+        let param = attach_params(detach_keys, logs, true, true, true, true)?;
+        let mut headers = self.headers().clone();
+        headers.insert(
+            http::header::CONNECTION,
+            HeaderValue::from_static("Upgrade"),
+        );
+        headers.insert(http::header::UPGRADE, HeaderValue::from_static("tcp"));
+        let res = self
+            .http_client()
+            .post_stream(
+                &headers,
+                &format!("/containers/{}/attach?{}", id, param),
+                "",
+            )
+            .await?;
+        if res.status() != StatusCode::SWITCHING_PROTOCOLS {
+            return if res.status().is_success() {
+                Err(DwError::Unknown {
+                    message: format!("attach to {id}: the daemon did not upgrade the connection"),
+                })
+            } else {
+                Err(into_docker_error(res).await?.into())
+            };
         }
+        let (stdout, stdin) = tokio::io::split(hyper::upgrade::on(res).await?);
+        Ok((AttachStdin { stdin }, read_aframes(stdout)))
     }
 
     /// List existing checkpoints from container
@@ -679,10 +1578,38 @@ impl Docker {
         if res.status().is_success() {
             into_aframe_stream(res.into_body()).await
         } else {
-            Err(into_docker_error(res.into_body()).await?.into())
+            Err(into_docker_error(res).await?.into())
         }
     }
 
+    /// Start an exec instance without attaching to it
+    ///
+    /// # Summary
+    /// `option` is sent with `detach` set, so this returns as soon as the command is started.
+    /// Use `exec_inspect` to check whether the command has finished.
+    ///
+    /// # API
+    /// /exec/{id}/start
+    pub async fn start_exec_detached(
+        &self,
+        id: &str,
+        option: &StartExecOptions,
+    ) -> Result<(), DwError> {
+        let json_body = serde_json::to_string(option.clone().detach(true))?;
+
+        let mut headers = self.headers().clone();
+        headers.insert(
+            http::header::CONTENT_TYPE,
+            "application/json".parse().unwrap(),
+        );
+
+        let res = self
+            .http_client()
+            .post(&headers, &format!("/exec/{id}/start"), &json_body)
+            .await?;
+        ignore_result(res)
+    }
+
     /// Inspect an exec instance
     ///
     /// Return low-level information about an exec instance.
@@ -717,8 +1644,79 @@ impl Docker {
         if res.status().is_success() {
             into_lines(res.into_body())
         } else {
-            Err(into_docker_error(res.into_body()).await?.into())
+            Err(into_docker_error(res).await?.into())
+        }
+    }
+
+    /// Get the last `n` lines of logs from a container
+    ///
+    /// # Summary
+    /// Unlike `log_container`, this does not follow the logs.
+    /// Output of a container without tty is multiplexed by the daemon,
+    /// so this demultiplexes stdout and stderr before splitting it into lines.
+    /// `n` greater than `i64::MAX` is clamped to it.
+    ///
+    /// # API
+    /// /containers/{id}/logs
+    pub async fn tail_logs(&self, id: &str, n: u64) -> Result<Vec<String>, DwError> {
+        use futures::stream::TryStreamExt;
+        let tty = self.container_info(id).await?.Config.Tty;
+        let option = ContainerLogOptions {
+            // a negative tail means all lines for the daemon
+            tail: Some(i64::try_from(n).unwrap_or(i64::MAX)),
+            follow: false,
+            ..ContainerLogOptions::default()
+        };
+        let res = self
+            .http_client()
+            .get_stream(
+                self.headers(),
+                &format!("/containers/{}/logs?{}", id, option.to_url_params()),
+            )
+            .await?;
+        if !res.status().is_success() {
+            return Err(into_docker_error(res).await?.into());
+        }
+        into_log_lines(res.into_body(), tty)
+            .await?
+            .try_collect()
+            .await
+    }
+
+    /// Gets logs from a container with parsed timestamps
+    ///
+    /// # Summary
+    /// Each line is yielded with the timestamp which the daemon prepends to the line.
+    /// `timestamps` of the `option` is ignored (always enabled).
+    ///
+    /// # API
+    /// /containers/{id}/logs
+    pub async fn log_container_timestamped(
+        &self,
+        id: &str,
+        option: &ContainerLogOptions,
+    ) -> Result<BoxStream<'static, Result<(DateTime<FixedOffset>, String), DwError>>, DwError> {
+        use futures::stream::StreamExt;
+        let tty = self.container_info(id).await?.Config.Tty;
+        let option = ContainerLogOptions {
+            timestamps: Some(true),
+            ..option.clone()
+        };
+        let res = self
+            .http_client()
+            .get_stream(
+                self.headers(),
+                &format!("/containers/{}/logs?{}", id, option.to_url_params()),
+            )
+            .await?;
+        if !res.status().is_success() {
+            return Err(into_docker_error(res).await?.into());
         }
+        let stream = into_log_lines(res.into_body(), tty)
+            .await?
+            .map(|line| line.and_then(|line| split_log_timestamp(&line)))
+            .boxed();
+        Ok(stream)
     }
 
     /// List processes running inside a container
@@ -775,23 +1773,127 @@ impl Docker {
         stream: Option<bool>,
         oneshot: Option<bool>,
     ) -> Result<BoxStream<'static, Result<Stats, DwError>>, DwError> {
-        let mut query = url::form_urlencoded::Serializer::new(String::new());
-        query.append_pair("stream", &stream.unwrap_or(true).to_string());
-        query.append_pair("one-shot", &oneshot.unwrap_or(false).to_string());
+        let param = {
+            let mut param = url::form_urlencoded::Serializer::new(String::new());
+            param.append_pair("stream", &stream.unwrap_or(true).to_string());
+            param.append_pair("one-shot", &oneshot.unwrap_or(false).to_string());
+            param.finish()
+        };
         let res = self
             .http_client()
             .get_stream(
                 self.headers(),
-                &format!("/containers/{}/stats?{}", container_id, query.finish()),
+                &format!("/containers/{}/stats?{}", container_id, param),
             )
             .await?;
         if res.status().is_success() {
             into_jsonlines(res.into_body())
         } else {
-            Err(into_docker_error(res.into_body()).await?.into())
+            Err(into_docker_error(res).await?.into())
         }
     }
 
+    /// Stream resource usage of a container, like `docker stats`
+    ///
+    /// # Summary
+    /// Each frame of `stats` is converted to a `StatsSummary`.
+    /// The cpu usage of the first frame is not available.
+    ///
+    /// # API
+    /// GET /containers/{id}/stats
+    pub fn stats_summary(&self, id: &str) -> BoxStream<'static, Result<StatsSummary, DwError>> {
+        use futures::stream::{StreamExt, TryStreamExt};
+        let docker = self.clone();
+        let id = id.to_owned();
+        futures::stream::once(async move { docker.stats(&id, Some(true), None).await })
+            .map_ok(|stats| stats.map_ok(|stats| StatsSummary::from(&stats)))
+            .try_flatten()
+            .boxed()
+    }
+
+    /// List running containers, each paired with a one-shot stats sample
+    ///
+    /// # Summary
+    /// Containers which are not running, or removed before their stats are fetched, are skipped.
+    /// Stats are fetched concurrently, with a bounded number of requests in flight.
+    ///
+    /// # API
+    /// /containers/json, /containers/{id}/stats
+    pub async fn list_containers_with_stats(
+        &self,
+        filters: ContainerFilters,
+    ) -> Result<Vec<(Container, Stats)>, DwError> {
+        use futures::stream::{StreamExt, TryStreamExt};
+        let containers = self.list_containers(None, None, None, filters).await?;
+        futures::stream::iter(containers)
+            .filter(|container| futures::future::ready(container.State == "running"))
+            .map(|container| async move {
+                let mut stats = match self.stats(&container.Id, Some(false), Some(true)).await {
+                    Ok(stats) => stats,
+                    // removed after listed
+                    Err(DwError::Docker(err)) if err.kind() == DockerErrorKind::NotFound => {
+                        return Ok(None)
+                    }
+                    Err(err) => return Err(err),
+                };
+                let stats = stats.next().await.transpose()?;
+                Ok(stats.map(|stats| (container, stats)))
+            })
+            .buffered(CONCURRENT_REQUESTS)
+            .try_filter_map(futures::future::ok)
+            .try_collect()
+            .await
+    }
+
+    /// Get stats of a container, reconnecting when the connection is lost
+    ///
+    /// # Summary
+    /// When the connection to the daemon is reset or refused, reopens the stats stream.
+    /// Other errors, such as the container being removed, are yielded and end the stream.
+    /// The stream also ends when the daemon closes it normally, e.g. after the container stops.
+    ///
+    /// # API
+    /// /containers/{id}/stats
+    pub fn stats_resilient(&self, id: &str) -> BoxStream<'static, Result<Stats, DwError>> {
+        use futures::stream::StreamExt;
+        let docker = self.clone();
+        let id = id.to_owned();
+        let stream = async_stream::stream! {
+            loop {
+                let mut stats = match docker.stats(&id, Some(true), None).await {
+                    Ok(stats) => stats,
+                    Err(err) if is_connection_error(&err) => {
+                        tokio::time::sleep(RECONNECT_INTERVAL).await;
+                        continue;
+                    }
+                    Err(err) => {
+                        yield Err(err);
+                        break;
+                    }
+                };
+                let mut lost = false;
+                while let Some(stat) = stats.next().await {
+                    match stat {
+                        Ok(stat) => yield Ok(stat),
+                        Err(err) if is_connection_error(&err) => {
+                            lost = true;
+                            break;
+                        }
+                        Err(err) => {
+                            yield Err(err);
+                            break;
+                        }
+                    }
+                }
+                if !lost {
+                    break;
+                }
+                tokio::time::sleep(RECONNECT_INTERVAL).await;
+            }
+        };
+        stream.boxed()
+    }
+
     /// Wait for a container
     ///
     /// # API
@@ -804,6 +1906,40 @@ impl Docker {
         api_result(res).map_err(Into::into)
     }
 
+    /// Watch state transitions of a container
+    ///
+    /// # Summary
+    /// Yields a status each time the container changes its state.
+    /// The stream ends when the container is destroyed, without yielding a status for it.
+    ///
+    /// # API
+    /// /events
+    pub async fn watch_container(
+        &self,
+        id: &str,
+    ) -> Result<BoxStream<'static, Result<ContainerStatus, DwError>>, DwError> {
+        use futures::stream::StreamExt;
+        let mut filters = EventFilters::new();
+        filters.type_("container").container(id);
+        for action in CONTAINER_STATE_EVENTS {
+            filters.event(action);
+        }
+        let events = self.events(None, None, Some(filters)).await?;
+        let stream = events
+            .take_while(|event| {
+                let destroyed = matches!(event, Ok(event) if event.Action == "destroy");
+                futures::future::ready(!destroyed)
+            })
+            .filter_map(|event| async move {
+                match event {
+                    Ok(event) => container_status_of_event(&event.Action).map(Ok),
+                    Err(err) => Some(Err(err)),
+                }
+            })
+            .boxed();
+        Ok(stream)
+    }
+
     /// Remove a container
     ///
     /// # API
@@ -829,6 +1965,33 @@ impl Docker {
         no_content(res).map_err(Into::into)
     }
 
+    /// Wait until a container is removed
+    ///
+    /// # Summary
+    /// Removal of a container may still be in progress after `remove_container`
+    /// or the exit of an auto-removed container.
+    /// This method polls the container until the daemon reports it as not found,
+    /// and fails with `DwError::Timeout` if it still exists after `timeout`.
+    ///
+    /// # API
+    /// /containers/{id}/json
+    pub async fn wait_container_removed(&self, id: &str, timeout: Duration) -> Result<(), DwError> {
+        let poll = async {
+            loop {
+                match self.container_info(id).await {
+                    Ok(_) => tokio::time::sleep(REMOVAL_POLL_INTERVAL).await,
+                    Err(DwError::Docker(err)) if err.kind() == DockerErrorKind::NotFound => {
+                        return Ok(())
+                    }
+                    Err(err) => return Err(err),
+                }
+            }
+        };
+        tokio::time::timeout(timeout, poll)
+            .await
+            .unwrap_or(Err(DwError::Timeout))
+    }
+
     /// Get an archive of a filesystem resource in a container
     ///
     /// # API
@@ -856,12 +2019,33 @@ impl Docker {
             use futures::stream::TryStreamExt;
             Ok(res.into_body().map_err(DwError::from).boxed())
         } else {
-            Err(into_docker_error(res.into_body()).await?.into())
+            Err(into_docker_error(res).await?.into())
         }
     }
 
+    /// Copy files out of a container
+    ///
+    /// # Summary
+    /// Parses the archive of `src` on the fly and yields the path and the contents of each regular file.
+    /// Paths are relative to the parent of `src`, e.g. `hosts` for `/etc/hosts`.
+    /// Directories, links and other special files are skipped.
+    ///
+    /// # API
+    /// /containers/{id}/archive
+    pub async fn copy_out(
+        &self,
+        id: &str,
+        src: &Path,
+    ) -> Result<BoxStream<'static, Result<(PathBuf, Bytes), DwError>>, DwError> {
+        let archive = self.get_file(id, src).await?;
+        Ok(into_tar_files(archive))
+    }
+
     /// Get information about files in a container
     ///
+    /// # Summary
+    /// Fails with a `NotFound` docker error if the container or the path does not exist.
+    ///
     /// # API
     /// /containers/{id}/archive
     pub async fn head_file(
@@ -882,7 +2066,20 @@ impl Docker {
                 &format!("/containers/{}/archive?{}", id, param),
             )
             .await?;
+        if !res.status().is_success() {
+            // the response to HEAD has no body
+            return Err(DockerError {
+                message: res
+                    .status()
+                    .canonical_reason()
+                    .unwrap_or("unknown error")
+                    .to_owned(),
+                status: Some(res.status()),
+            }
+            .into());
+        }
         let stat_base64: &str = res
+            .headers()
             .get("X-Docker-Container-Path-Stat")
             .map(|h| h.to_str().unwrap_or(""))
             .unwrap_or("");
@@ -896,6 +2093,26 @@ impl Docker {
         Ok(path_stat)
     }
 
+    /// Check if a path exists in a container
+    ///
+    /// # Summary
+    /// Returns `Ok(false)` if the path does not exist.
+    /// Fails if the container does not exist.
+    ///
+    /// # API
+    /// /containers/{id}/archive
+    pub async fn file_exists(&self, id: &str, path: &Path) -> Result<bool, DwError> {
+        match self.head_file(id, path).await {
+            Ok(_) => Ok(true),
+            Err(DwError::Docker(err)) if err.kind() == DockerErrorKind::NotFound => {
+                // the response does not tell whether the container or the path is missing
+                self.container_info(id).await?;
+                Ok(false)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
     /// Extract an archive of files or folders to a directory in a container
     ///
     /// # Summary
@@ -923,31 +2140,58 @@ impl Docker {
             dst.display(),
             noOverwriteDirNonDir
         );
-        let param = {
-            let mut param = url::form_urlencoded::Serializer::new(String::new());
-            param.append_pair("path", &dst.to_string_lossy());
-            param.append_pair("noOverwriteDirNonDir", &noOverwriteDirNonDir.to_string());
-            param.finish()
-        };
         let res = self
             .http_client()
             .put_file(
                 self.headers(),
-                &format!("/containers/{}/archive?{}", id, param),
+                &archive_path(id, dst, noOverwriteDirNonDir),
                 src,
             )
             .await?;
         ignore_result(res).map_err(Into::into)
     }
 
-    /// Build an image from a tar archive with a Dockerfile in it.
+    /// Same as `put_file`, but calls `progress` with the cumulative number of bytes sent
+    /// each time a chunk of `src` is uploaded.
     ///
     /// # API
-    /// /build?
-    pub async fn build_image(
+    /// PUT /containers/{id}/archive
+    #[allow(non_snake_case)]
+    pub async fn put_file_with_progress(
         &self,
-        options: ContainerBuildOptions,
-        tar_path: &Path,
+        id: &str,
+        src: &Path,
+        dst: &Path,
+        noOverwriteDirNonDir: bool,
+        progress: impl FnMut(u64) + Send + 'static,
+    ) -> Result<(), DwError> {
+        debug!(
+            "put_file_with_progress({}, {}, {}, {})",
+            id,
+            src.display(),
+            dst.display(),
+            noOverwriteDirNonDir
+        );
+        let body = file_body_with_progress(src, progress).await?;
+        let res = self
+            .http_client()
+            .put_body(
+                self.headers(),
+                &archive_path(id, dst, noOverwriteDirNonDir),
+                body,
+            )
+            .await?;
+        ignore_result(res).map_err(Into::into)
+    }
+
+    /// Build an image from a tar archive with a Dockerfile in it.
+    ///
+    /// # API
+    /// /build?
+    pub async fn build_image(
+        &self,
+        options: ContainerBuildOptions,
+        tar_path: &Path,
     ) -> Result<BoxStream<'static, Result<DockerResponse, DwError>>, DwError> {
         let mut headers = self.headers().clone();
         headers.insert(
@@ -965,7 +2209,37 @@ impl Docker {
         if res.status().is_success() {
             into_jsonlines(res.into_body())
         } else {
-            Err(into_docker_error(res.into_body()).await?.into())
+            Err(into_docker_error(res).await?.into())
+        }
+    }
+
+    /// Build an image from the context at `options.remote`.
+    ///
+    /// Unlike `build_image`, no tar archive is uploaded.
+    /// The request body is empty, so `options.remote` must be set;
+    /// fails with `DwError::MissingRemote` otherwise.
+    ///
+    /// # API
+    /// /build?remote={remote}
+    pub async fn build_image_remote(
+        &self,
+        options: ContainerBuildOptions,
+    ) -> Result<BoxStream<'static, Result<DockerResponse, DwError>>, DwError> {
+        if options.remote.is_none() {
+            return Err(DwError::MissingRemote);
+        }
+        let res = self
+            .http_client()
+            .post_stream(
+                self.headers(),
+                &format!("/build?{}", options.to_url_params()),
+                "",
+            )
+            .await?;
+        if res.status().is_success() {
+            into_jsonlines(res.into_body())
+        } else {
+            Err(into_docker_error(res).await?.into())
         }
     }
 
@@ -986,24 +2260,27 @@ impl Docker {
         &self,
         image: &str,
         tag: &str,
+    ) -> Result<BoxStream<'static, Result<DockerResponse, DwError>>, DwError> {
+        self.post_create_image(image, tag, None).await
+    }
+
+    async fn post_create_image(
+        &self,
+        image: &str,
+        tag: &str,
+        platform: Option<&str>,
     ) -> Result<BoxStream<'static, Result<DockerResponse, DwError>>, DwError> {
         let param = {
             let mut param = url::form_urlencoded::Serializer::new(String::new());
             param.append_pair("fromImage", image);
             param.append_pair("tag", tag);
+            if let Some(platform) = platform {
+                param.append_pair("platform", platform);
+            }
             param.finish()
         };
 
-        let mut headers = self.headers().clone();
-        if let Some(ref credential) = self.credential.lock().unwrap().as_ref() {
-            headers.insert(
-                "X-Registry-Auth",
-                general_purpose::STANDARD
-                    .encode(serde_json::to_string(credential).unwrap().as_bytes())
-                    .parse()
-                    .unwrap(),
-            );
-        }
+        let headers = self.headers_with_registry_auth()?;
         let res = self
             .http_client()
             .post_stream(&headers, &format!("/images/create?{}", param), "")
@@ -1011,8 +2288,87 @@ impl Docker {
         if res.status().is_success() {
             into_jsonlines(res.into_body())
         } else {
-            Err(into_docker_error(res.into_body()).await?.into())
+            Err(into_docker_error(res).await?.into())
+        }
+    }
+
+    /// Pull an image and wait for the pull to complete
+    async fn pull_image(
+        &self,
+        image: &str,
+        tag: &str,
+        platform: Option<&str>,
+    ) -> Result<(), DwError> {
+        use futures::stream::StreamExt;
+        let mut progress = self.post_create_image(image, tag, platform).await?;
+        while let Some(response) = progress.next().await {
+            if let Some(err) = response?.as_error() {
+                return Err(err.clone().into());
+            }
         }
+        Ok(())
+    }
+
+    /// Pull an image and return its digest like `alpine@sha256:...`
+    ///
+    /// # Summary
+    /// Waits for the pull to complete, then looks up the digest of the repository `image`
+    /// in `RepoDigests` of the pulled image.
+    ///
+    /// # API
+    /// /images/create?fromImage={image}&tag={tag}
+    /// /images/{name}/json
+    pub async fn pull_image_digest(&self, image: &str, tag: &str) -> Result<String, DwError> {
+        self.pull_image(image, tag, None).await?;
+        let info = self.inspect_image(&format!("{image}:{tag}")).await?;
+        repo_digest_of(&info.RepoDigests, image)
+            .map(ToOwned::to_owned)
+            .ok_or_else(|| DwError::Unknown {
+                message: format!("no digest of {image} in the image {image}:{tag}"),
+            })
+    }
+
+    /// Pull images concurrently
+    ///
+    /// # Summary
+    /// Pulls each `(image, tag)` in `refs`, with a bounded number of pulls at a time.
+    /// Progress messages of all pulls are merged, each tagged with `image:tag`.
+    /// An error message from the daemon is yielded as `Err`.
+    ///
+    /// # API
+    /// /images/create
+    pub fn pull_images(
+        &self,
+        refs: &[(&str, &str)],
+    ) -> BoxStream<'static, Result<(String, PullProgress), DwError>> {
+        use futures::stream::{StreamExt, TryStreamExt};
+        let docker = self.clone();
+        let refs: Vec<(String, String)> = refs
+            .iter()
+            .map(|(image, tag)| (image.to_string(), tag.to_string()))
+            .collect();
+        futures::stream::iter(refs)
+            .map(move |(image, tag)| {
+                let docker = docker.clone();
+                futures::stream::once(async move {
+                    let reference = format!("{image}:{tag}");
+                    match docker.create_image(&image, &tag).await {
+                        Ok(responses) => responses
+                            .try_filter_map(|response| async move {
+                                PullProgress::from_response(response)
+                                    .transpose()
+                                    .map_err(Into::into)
+                            })
+                            .map_ok(move |progress| (reference.clone(), progress))
+                            .boxed(),
+                        Err(err) => futures::stream::once(async move { Err(err) }).boxed(),
+                    }
+                })
+                .flatten()
+                .boxed()
+            })
+            .flatten_unordered(CONCURRENT_REQUESTS)
+            .boxed()
     }
 
     /// Inspect an image
@@ -1028,6 +2384,134 @@ impl Docker {
         api_result(res).map_err(Into::into)
     }
 
+    /// Id of an image (the `sha256:` digest of its config)
+    ///
+    /// Returns `None` if the image does not exist.
+    ///
+    /// # API
+    /// /images/{name}/json
+    pub async fn image_id(&self, name: &str) -> Result<Option<ImageId>, DwError> {
+        match self.inspect_image(name).await {
+            Ok(image) => Ok(Some(ImageId::new(image.Id))),
+            Err(DwError::Docker(err)) if err.kind() == DockerErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Create a new image from a container
+    ///
+    /// # Summary
+    /// * `config` - overrides the configuration of the container in the new image
+    ///
+    /// # API
+    /// /commit
+    pub async fn commit_container(
+        &self,
+        options: &CommitContainerOptions,
+        config: Option<&Config>,
+    ) -> Result<CommitResponse, DwError> {
+        let path = format!("/commit?{}", options.to_url_params());
+        let res = match config {
+            Some(config) => {
+                let json_body = serde_json::to_string(config)?;
+                let mut headers = self.headers().clone();
+                headers.insert(
+                    http::header::CONTENT_TYPE,
+                    "application/json".parse().unwrap(),
+                );
+                self.http_client().post(&headers, &path, &json_body).await?
+            }
+            None => self.http_client().post(self.headers(), &path, "").await?,
+        };
+        api_result(res)
+    }
+
+    /// Add labels to an existing image
+    ///
+    /// # Summary
+    /// A throwaway container created from `name` is committed with `LABEL` changes,
+    /// and `name` is tagged to the new image.
+    /// The container is removed afterwards, even if committing fails.
+    /// A reference by digest can not be tagged, so the new image is left untagged.
+    ///
+    /// # API
+    /// GET /images/{name}/json, POST /containers/create, POST /commit
+    pub async fn label_image(
+        &self,
+        name: &str,
+        labels: HashMap<String, String>,
+    ) -> Result<ImageId, DwError> {
+        let image = self.inspect_image(name).await?;
+        let mut create = ContainerCreateOptions::new(name);
+        if let Some(stop_signal) = image.Config.StopSignal {
+            create.stop_signal(stop_signal);
+        }
+        // the daemon refuses to create a container without a command;
+        // the placeholder never runs, and is reset on commit
+        let placeholder = image.Config.Cmd.is_empty() && image.Config.Entrypoint.is_empty();
+        if placeholder {
+            create.cmd("true".to_owned());
+        }
+        let container = self.create_container_guarded(None, &create).await?;
+
+        let mut options = CommitContainerOptions::new(container.id());
+        if !name.contains('@') {
+            let (repository, tag) = split_image_reference(name);
+            options.repo(repository).tag(tag);
+        }
+        if placeholder {
+            options.change("CMD []");
+        }
+        let mut labels: Vec<_> = labels.iter().collect();
+        labels.sort();
+        for (key, value) in labels {
+            options.change(&label_change(key, value));
+        }
+        let res = self.commit_container(&options, None).await;
+        let removed = container.remove().await;
+        let res = res?;
+        removed?;
+        Ok(ImageId::new(res.id))
+    }
+
+    /// Tag an image as `repo:tag`
+    ///
+    /// # Summary
+    /// * `force` - overwrite the existing tag (ignored by daemons of API 1.25 or later, which always overwrite)
+    ///
+    /// # API
+    /// /images/{name}/tag
+    pub async fn tag_image(
+        &self,
+        name: &str,
+        repo: &str,
+        tag: &str,
+        force: bool,
+    ) -> Result<(), DwError> {
+        let param = {
+            let mut param = url::form_urlencoded::Serializer::new(String::new());
+            param.append_pair("repo", repo);
+            param.append_pair("tag", tag);
+            if force {
+                param.append_pair("force", "true");
+            }
+            param.finish()
+        };
+        let res = self
+            .http_client()
+            .post(
+                self.headers(),
+                &format!("/images/{}/tag?{}", name, param),
+                "",
+            )
+            .await?;
+        if res.status() == StatusCode::CREATED {
+            Ok(())
+        } else {
+            Err(docker_error(res.status(), res.body())?.into())
+        }
+    }
+
     /// Push an image
     ///
     /// # NOTE
@@ -1043,16 +2527,7 @@ impl Docker {
             param.append_pair("tag", tag);
             param.finish()
         };
-        let mut headers = self.headers().clone();
-        if let Some(ref credential) = self.credential.lock().unwrap().as_ref() {
-            headers.insert(
-                "X-Registry-Auth",
-                general_purpose::STANDARD
-                    .encode(serde_json::to_string(credential).unwrap().as_bytes())
-                    .parse()
-                    .unwrap(),
-            );
-        }
+        let headers = self.headers_with_registry_auth()?;
         let res = self
             .http_client()
             .post(&headers, &format!("/images/{}/push?{}", name, param), "")
@@ -1060,6 +2535,34 @@ impl Docker {
         ignore_result(res).map_err(Into::into)
     }
 
+    /// Push all tags of an image
+    ///
+    /// # Summary
+    /// Same as `docker push --all-tags`. Returns a stream of progress messages of all tags.
+    ///
+    /// # NOTE
+    /// For pushing an image to non default registry, add registry id to prefix of the image name like `<registry>/<image>` .
+    ///
+    /// # API
+    /// /images/{name}/push
+    ///
+    pub async fn push_image_all_tags(
+        &self,
+        name: &str,
+    ) -> Result<BoxStream<'static, Result<DockerResponse, DwError>>, DwError> {
+        let headers = self.headers_with_registry_auth()?;
+        // pushing without a tag pushes all tags of the repository
+        let res = self
+            .http_client()
+            .post_stream(&headers, &format!("/images/{name}/push"), "")
+            .await?;
+        if res.status().is_success() {
+            into_jsonlines(res.into_body())
+        } else {
+            Err(into_docker_error(res).await?.into())
+        }
+    }
+
     /// Remove an image
     ///
     /// # API
@@ -1127,6 +2630,34 @@ impl Docker {
             })
     }
 
+    /// Size of an image in bytes
+    ///
+    /// # API
+    /// /images/{name}/json
+    pub async fn image_size(&self, name: &str) -> Result<u64, DwError> {
+        let image = self.inspect_image(name).await?;
+        Ok(image.Size.max(0) as u64)
+    }
+
+    /// Sizes of the layers of an image, paired with the layer id
+    ///
+    /// # Summary
+    /// Layers are listed from the newest one, as `history_image` does.
+    /// The id is `None` for layers built on another host.
+    ///
+    /// # API
+    /// /images/{name}/history
+    pub async fn image_layer_sizes(
+        &self,
+        name: &str,
+    ) -> Result<Vec<(Option<String>, u64)>, DwError> {
+        let layers = self.history_image(name).await?;
+        Ok(layers
+            .into_iter()
+            .map(|layer| (layer.id, layer.size))
+            .collect())
+    }
+
     /// List images
     ///
     /// # API
@@ -1139,6 +2670,39 @@ impl Docker {
         api_result(res).map_err(Into::into)
     }
 
+    /// List images matching `filters`
+    ///
+    /// # API
+    /// /images/json
+    pub async fn list_images(
+        &self,
+        all: bool,
+        filters: ListImageFilters,
+    ) -> Result<Vec<SummaryImage>, DwError> {
+        let param = {
+            let mut param = url::form_urlencoded::Serializer::new(String::new());
+            param.append_pair("a", &(all as u32).to_string());
+            param.append_pair("filters", &serde_json::to_string(&filters)?);
+            param.finish()
+        };
+        let res = self
+            .http_client()
+            .get(self.headers(), &format!("/images/json?{param}"))
+            .await?;
+        api_result(res)
+    }
+
+    /// List `repo:tag` of all images
+    ///
+    /// Dangling entries (`<none>:<none>`) are skipped. The tags are sorted.
+    ///
+    /// # API
+    /// /images/json
+    pub async fn image_tags(&self) -> Result<Vec<String>, DwError> {
+        let images = self.images(false).await?;
+        Ok(repo_tags(&images))
+    }
+
     /// Search for an image on Docker Hub.
     ///
     /// # API
@@ -1182,7 +2746,7 @@ impl Docker {
             use futures::stream::TryStreamExt;
             Ok(res.into_body().map_err(Into::into).boxed())
         } else {
-            Err(into_docker_error(res.into_body()).await?.into())
+            Err(into_docker_error(res).await?.into())
         }
     }
 
@@ -1204,31 +2768,36 @@ impl Docker {
             .post_file(&headers, &format!("/images/load?quiet={quiet}"), path)
             .await?;
         if !res.status().is_success() {
-            return Err(serde_json::from_slice::<DockerError>(res.body())?.into());
+            return Err(docker_error(res.status(), res.body())?.into());
         }
-        let path = path.to_owned();
-        tokio::task::spawn_blocking(move || {
-            let file = std::fs::File::open(path)?;
-            let mut ar = tar::Archive::new(file);
-            for entry in ar.entries()?.filter_map(|e| e.ok()) {
-                let path = entry.path()?;
-                // looking for file name like XXXXXXXXXXXXXX.json
-                if path.extension() == Some(std::ffi::OsStr::new("json"))
-                    && path != Path::new("manifest.json")
-                {
-                    let stem = path.file_stem().unwrap(); // contains .json
-                    let id = stem.to_str().ok_or(DwError::Unknown {
-                        message: format!("convert to String: {stem:?}"),
-                    })?;
-                    return Ok(ImageId::new(id.to_string()));
-                }
-            }
-            Err(DwError::Unknown {
-                message: "no expected file: XXXXXX.json".to_owned(),
-            })
-        })
-        .await
-        .expect("join error")
+        image_id_in_archive(path).await
+    }
+
+    /// Same as `load_image`, but calls `progress` with the cumulative number of bytes sent
+    /// each time a chunk of the archive is uploaded.
+    ///
+    /// # API
+    /// /images/load
+    pub async fn load_image_with_progress(
+        &self,
+        quiet: bool,
+        path: &Path,
+        progress: impl FnMut(u64) + Send + 'static,
+    ) -> Result<ImageId, DwError> {
+        let mut headers = self.headers().clone();
+        headers.insert(
+            http::header::CONTENT_TYPE,
+            "application/x-tar".parse().unwrap(),
+        );
+        let body = file_body_with_progress(path, progress).await?;
+        let res = self
+            .http_client()
+            .post_body(&headers, &format!("/images/load?quiet={quiet}"), body)
+            .await?;
+        if !res.status().is_success() {
+            return Err(docker_error(res.status(), res.body())?.into());
+        }
+        image_id_in_archive(path).await
     }
 
     /// Check auth configuration
@@ -1274,6 +2843,22 @@ impl Docker {
         api_result(res).map_err(Into::into)
     }
 
+    /// Whether the daemon is a node of an active swarm
+    ///
+    /// # Summary
+    /// Check this before calling service or node apis,
+    /// which fail with 503 on a daemon not in swarm mode.
+    ///
+    /// # API
+    /// /info
+    pub async fn swarm_active(&self) -> Result<bool, DwError> {
+        let info = self.system_info().await?;
+        Ok(matches!(
+            info.Swarm.map(|swarm| swarm.LocalNodeState),
+            Some(LocalNodeState::Active)
+        ))
+    }
+
     /// Inspect about a container
     ///
     /// # API
@@ -1286,38 +2871,113 @@ impl Docker {
         api_result(res).map_err(Into::into)
     }
 
-    /// Get changes on a container's filesystem.
+    /// Inspect about a container as untyped JSON
     ///
-    /// (This is the same as `docker container diff` command.)
+    /// # Summary
+    /// All the fields reported by the daemon are kept,
+    /// including those not modeled by `ContainerInfo`.
     ///
     /// # API
-    /// /containers/{id}/changes
-    pub async fn filesystem_changes(
+    /// /containers/{id}/json
+    pub async fn container_info_raw(
         &self,
         container_id: &str,
-    ) -> Result<Vec<FilesystemChange>, DwError> {
+    ) -> Result<serde_json::Value, DwError> {
         let res = self
             .http_client()
-            .get(
-                self.headers(),
-                &format!("/containers/{container_id}/changes"),
-            )
+            .get(self.headers(), &format!("/containers/{container_id}/json"))
             .await?;
-        api_result(res).map_err(Into::into)
+        api_result(res)
     }
 
-    /// Export a container
+    /// Resolve the name of a container to its ID
     ///
     /// # Summary
-    /// Returns a pointer to tar archive stream.
+    /// `name` is matched exactly, with or without the leading `/`.
+    /// Returns `None` if no container has the name.
     ///
     /// # API
-    /// /containers/{id}/export
-    pub async fn export_container(
-        &self,
-        container_id: &str,
-    ) -> Result<BoxStream<'static, Result<Bytes, DwError>>, DwError> {
-        let res = self
+    /// /containers/json
+    pub async fn container_id_of(&self, name: &str) -> Result<Option<String>, DwError> {
+        let name = format!("/{}", name.trim_start_matches('/'));
+        let mut filters = ContainerFilters::new();
+        filters.name(&format!("^{name}$"));
+        let containers = self
+            .list_containers(Some(true), None, None, filters)
+            .await?;
+        Ok(containers
+            .into_iter()
+            .find(|container| container.Names.contains(&name))
+            .map(|container| container.Id))
+    }
+
+    /// Resolve the ID of a container to its name without the leading `/`
+    ///
+    /// # Summary
+    /// Returns `None` if no container has the ID.
+    ///
+    /// # API
+    /// /containers/{id}/json
+    pub async fn container_name_of(&self, id: &str) -> Result<Option<String>, DwError> {
+        match self.container_info(id).await {
+            Ok(info) => Ok(Some(info.Name.trim_start_matches('/').to_owned())),
+            Err(DwError::Docker(err)) if err.kind() == DockerErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Inspect multiple containers concurrently
+    ///
+    /// # Summary
+    /// Requests are sent with a bounded number of requests in flight.
+    /// Results are returned in the order of `ids`, each paired with its id.
+    ///
+    /// # API
+    /// /containers/{id}/json
+    pub async fn inspect_containers(
+        &self,
+        ids: &[&str],
+    ) -> Vec<(String, Result<ContainerInfo, DwError>)> {
+        use futures::stream::StreamExt;
+        futures::stream::iter(ids)
+            .map(|id| async move { (id.to_string(), self.container_info(id).await) })
+            .buffered(CONCURRENT_REQUESTS)
+            .collect()
+            .await
+    }
+
+    /// Get changes on a container's filesystem.
+    ///
+    /// (This is the same as `docker container diff` command.)
+    ///
+    /// # API
+    /// /containers/{id}/changes
+    pub async fn filesystem_changes(
+        &self,
+        container_id: &str,
+    ) -> Result<Vec<FilesystemChange>, DwError> {
+        let res = self
+            .http_client()
+            .get(
+                self.headers(),
+                &format!("/containers/{container_id}/changes"),
+            )
+            .await?;
+        api_result(res).map_err(Into::into)
+    }
+
+    /// Export a container
+    ///
+    /// # Summary
+    /// Returns a pointer to tar archive stream.
+    ///
+    /// # API
+    /// /containers/{id}/export
+    pub async fn export_container(
+        &self,
+        container_id: &str,
+    ) -> Result<BoxStream<'static, Result<Bytes, DwError>>, DwError> {
+        let res = self
             .http_client()
             .get_stream(
                 self.headers(),
@@ -1329,10 +2989,33 @@ impl Docker {
             use futures::stream::TryStreamExt;
             Ok(res.into_body().map_err(Into::into).boxed())
         } else {
-            Err(into_docker_error(res.into_body()).await?.into())
+            Err(into_docker_error(res).await?.into())
         }
     }
 
+    /// Export the filesystem of a container into a directory
+    ///
+    /// # Summary
+    /// The exported archive is streamed and extracted into `dest` on a blocking task.
+    /// `dest` is created if it does not exist.
+    /// Fails on entries containing `..` instead of writing outside of `dest`.
+    /// Device files and fifos are skipped.
+    ///
+    /// # API
+    /// /containers/{id}/export
+    pub async fn export_container_to_dir(&self, id: &str, dest: &Path) -> Result<(), DwError> {
+        use futures::stream::TryStreamExt;
+        let stream = self.export_container(id).await?.map_err(|err| match err {
+            DwError::Io(err) => err,
+            err => std::io::Error::new(std::io::ErrorKind::Other, err),
+        });
+        let reader = tokio_util::io::SyncIoBridge::new(tokio_util::io::StreamReader::new(stream));
+        let dest = dest.to_owned();
+        tokio::task::spawn_blocking(move || unpack_archive(reader, &dest))
+            .await
+            .expect("join error")
+    }
+
     /// Test if the server is accessible
     ///
     /// # API
@@ -1344,10 +3027,63 @@ impl Docker {
             assert_eq!(&buf, "OK");
             Ok(())
         } else {
-            Err(serde_json::from_slice::<DockerError>(res.body())?.into())
+            Err(docker_error(res.status(), res.body())?.into())
         }
     }
 
+    /// Wait until the daemon is accessible
+    ///
+    /// # Summary
+    /// Retries `ping` with backoff while the connection is refused
+    /// or the socket does not exist yet, e.g. while the daemon is starting.
+    /// Fails with `DwError::Timeout` if the daemon is not ready after `timeout`,
+    /// or immediately on any other error.
+    ///
+    /// # API
+    /// /_ping
+    pub async fn wait_until_ready(&self, timeout: Duration) -> Result<(), DwError> {
+        let poll = async {
+            let mut interval = READY_POLL_INTERVAL;
+            loop {
+                match self.ping().await {
+                    Ok(()) => return Ok(()),
+                    Err(err) if is_daemon_starting(&err) => {
+                        tokio::time::sleep(interval).await;
+                        interval = (interval * 2).min(RECONNECT_INTERVAL);
+                    }
+                    Err(err) => return Err(err),
+                }
+            }
+        };
+        tokio::time::timeout(timeout, poll)
+            .await
+            .unwrap_or(Err(DwError::Timeout))
+    }
+
+    /// Get the API version of the daemon
+    ///
+    /// # Summary
+    /// Read `Api-Version` header of the ping response.
+    /// This is cheaper than `version`.
+    ///
+    /// # API
+    /// HEAD /_ping
+    pub async fn api_version(&self) -> Result<String, DwError> {
+        let res = self.http_client().head(self.headers(), "/_ping").await?;
+        let version = res
+            .headers()
+            .get("Api-Version")
+            .ok_or_else(|| DwError::Unknown {
+                message: "no Api-Version header in the ping response".to_owned(),
+            })?;
+        version
+            .to_str()
+            .map(ToOwned::to_owned)
+            .map_err(|err| DwError::Unknown {
+                message: format!("invalid Api-Version header: {err}"),
+            })
+    }
+
     /// Get version and various information
     ///
     /// # API
@@ -1391,6 +3127,83 @@ impl Docker {
         into_jsonlines(res.into_body())
     }
 
+    /// Get monitor events, reconnecting when the connection is lost
+    ///
+    /// # Summary
+    /// When the connection to the daemon is reset or refused, reconnects with `since`
+    /// set to the time of the last event, skipping events already yielded.
+    /// Other errors are yielded and end the stream.
+    ///
+    /// # API
+    /// /events
+    pub fn events_resilient(
+        &self,
+        filters: Option<EventFilters>,
+    ) -> BoxStream<'static, Result<EventResponse, DwError>> {
+        use futures::stream::StreamExt;
+        let docker = self.clone();
+        let stream = async_stream::stream! {
+            let mut connected_at = None;
+            // (time, timeNano) of the last event and events seen at that timeNano
+            let mut last: Option<(u64, u64)> = None;
+            let mut seen: Vec<(String, String, String)> = Vec::new();
+            loop {
+                let since = last.map(|(time, _)| time).or(connected_at);
+                let mut events = match docker.events(since, None, filters.clone()).await {
+                    Ok(events) => events,
+                    Err(err) if is_connection_error(&err) => {
+                        tokio::time::sleep(RECONNECT_INTERVAL).await;
+                        continue;
+                    }
+                    Err(err) => {
+                        yield Err(err);
+                        break;
+                    }
+                };
+                if connected_at.is_none() {
+                    connected_at = Some(chrono::Utc::now().timestamp() as u64);
+                }
+                let mut failure = None;
+                while let Some(event) = events.next().await {
+                    match event {
+                        Ok(event) => {
+                            let key = (
+                                event.Type.clone(),
+                                event.Action.clone(),
+                                event.Actor.ID.clone(),
+                            );
+                            match last {
+                                Some((_, nano)) if event.timeNano < nano => continue,
+                                Some((_, nano)) if event.timeNano == nano => {
+                                    if seen.contains(&key) {
+                                        continue;
+                                    }
+                                    seen.push(key);
+                                }
+                                _ => {
+                                    last = Some((event.time, event.timeNano));
+                                    seen = vec![key];
+                                }
+                            }
+                            yield Ok(event);
+                        }
+                        Err(err) if is_connection_error(&err) => break,
+                        Err(err) => {
+                            failure = Some(err);
+                            break;
+                        }
+                    }
+                }
+                if let Some(err) = failure {
+                    yield Err(err);
+                    break;
+                }
+                tokio::time::sleep(RECONNECT_INTERVAL).await;
+            }
+        };
+        stream.boxed()
+    }
+
     /// List networks
     ///
     /// # API
@@ -1491,6 +3304,23 @@ impl Docker {
         ignore_result(res).map_err(Into::into)
     }
 
+    /// Connect a container to a network with a static IPv4 address
+    ///
+    /// # Summary
+    /// The address must be in a subnet configured on the network (e.g. by `IPAM` on creation).
+    ///
+    /// # API
+    /// /networks/{id}/connect
+    pub async fn connect_network_with_ip(
+        &self,
+        network: &str,
+        container: &str,
+        ipv4: std::net::Ipv4Addr,
+    ) -> Result<(), DwError> {
+        self.connect_network(network, &NetworkConnectOptions::with_ipv4(container, ipv4))
+            .await
+    }
+
     /// Disconnect a container from a network
     ///
     /// # API
@@ -1532,6 +3362,180 @@ impl Docker {
         let res = self.http_client().post(self.headers(), &path, "").await?;
         api_result(res).map_err(Into::into)
     }
+
+    /// List volumes
+    ///
+    /// # API
+    /// /volumes
+    pub async fn list_volumes(
+        &self,
+        filters: ListVolumeFilters,
+    ) -> Result<ListVolumesResponse, DwError> {
+        let path = if filters.is_empty() {
+            "/volumes".to_string()
+        } else {
+            let mut param = url::form_urlencoded::Serializer::new(String::new());
+            debug!("filters: {}", serde_json::to_string(&filters)?);
+            param.append_pair("filters", &serde_json::to_string(&filters)?);
+            format!("/volumes?{}", param.finish())
+        };
+        let res = self.http_client().get(self.headers(), &path).await?;
+        api_result(res)
+    }
+
+    /// Inspect a volume
+    ///
+    /// # API
+    /// /volumes/{name}
+    pub async fn inspect_volume(&self, name: &str) -> Result<Volume, DwError> {
+        let res = self
+            .http_client()
+            .get(self.headers(), &format!("/volumes/{name}"))
+            .await?;
+        api_result(res)
+    }
+
+    /// Remove a volume
+    ///
+    /// # Summary
+    /// * `force` - remove the volume even if it is in use
+    ///
+    /// # API
+    /// /volumes/{name}
+    pub async fn remove_volume(&self, name: &str, force: bool) -> Result<(), DwError> {
+        let res = self
+            .http_client()
+            .delete(self.headers(), &format!("/volumes/{name}?force={force}"))
+            .await?;
+        no_content(res)
+    }
+
+    /// Create a volume
+    ///
+    /// # API
+    /// /volumes/create
+    pub async fn create_volume(&self, option: &VolumeCreateOptions) -> Result<Volume, DwError> {
+        let json_body = serde_json::to_string(&option)?;
+        let mut headers = self.headers().clone();
+        headers.insert(
+            http::header::CONTENT_TYPE,
+            "application/json".parse().unwrap(),
+        );
+        let res = self
+            .http_client()
+            .post(&headers, "/volumes/create", &json_body)
+            .await?;
+        api_result(res)
+    }
+
+    /// Delete unused volumes
+    ///
+    /// # API
+    /// /volumes/prune
+    pub async fn prune_volumes(
+        &self,
+        filters: PruneVolumeFilters,
+    ) -> Result<PruneVolumeResponse, DwError> {
+        let path = if filters.is_empty() {
+            "/volumes/prune".to_string()
+        } else {
+            let mut param = url::form_urlencoded::Serializer::new(String::new());
+            debug!("filters: {}", serde_json::to_string(&filters)?);
+            param.append_pair("filters", &serde_json::to_string(&filters)?);
+            format!("/volumes/prune?{}", param.finish())
+        };
+        let res = self.http_client().post(self.headers(), &path, "").await?;
+        api_result(res)
+    }
+
+    /// Delete stopped containers
+    ///
+    /// # API
+    /// /containers/prune
+    pub async fn prune_containers(
+        &self,
+        filters: PruneFilters,
+    ) -> Result<PrunedContainers, DwError> {
+        let path = if filters.is_empty() {
+            "/containers/prune".to_string()
+        } else {
+            let mut param = url::form_urlencoded::Serializer::new(String::new());
+            param.append_pair("filters", &serde_json::to_string(&filters)?);
+            format!("/containers/prune?{}", param.finish())
+        };
+        let res = self.http_client().post(self.headers(), &path, "").await?;
+        api_result(res)
+    }
+
+    /// Delete dangling images matching `filters`
+    async fn prune_dangling_images(&self, filters: &PruneFilters) -> Result<PrunedImages, DwError> {
+        let mut filters = serde_json::to_value(filters)?;
+        filters["dangling"] = serde_json::json!({ "true": true });
+        let mut param = url::form_urlencoded::Serializer::new(String::new());
+        param.append_pair("filters", &filters.to_string());
+        let res = self
+            .http_client()
+            .post(
+                self.headers(),
+                &format!("/images/prune?{}", param.finish()),
+                "",
+            )
+            .await?;
+        api_result(res)
+    }
+
+    /// Delete unused data like `docker system prune`
+    ///
+    /// # Summary
+    /// Prunes stopped containers, unused networks, dangling images and,
+    /// if `volumes` is true, unused volumes in this order,
+    /// so that objects released by a former step are pruned by the later steps.
+    /// `filters` apply to every step, except that volumes are not filtered by `until`.
+    ///
+    /// A failure of a step is recorded in the report and the remaining steps are still run.
+    /// If every step fails, e.g. the daemon is unreachable, the error of the first step is returned.
+    ///
+    /// # API
+    /// /containers/prune, /networks/prune, /images/prune and /volumes/prune
+    pub async fn system_prune(
+        &self,
+        volumes: bool,
+        filters: PruneFilters,
+    ) -> Result<SystemPruneReport, DwError> {
+        let mut report = SystemPruneReport::default();
+        let steps = if volumes { 4 } else { 3 };
+        match self.prune_containers(filters.clone()).await {
+            Ok(res) => {
+                report.containers_deleted = res.ContainersDeleted;
+                report.space_reclaimed += res.SpaceReclaimed;
+            }
+            Err(err) => report.errors.push(err),
+        }
+        match self.prune_networks(filters.networks()).await {
+            Ok(res) => report.networks_deleted = res.networks_deleted,
+            Err(err) => report.errors.push(err),
+        }
+        match self.prune_dangling_images(&filters).await {
+            Ok(res) => {
+                report.images_deleted = res.ImagesDeleted;
+                report.space_reclaimed += res.SpaceReclaimed;
+            }
+            Err(err) => report.errors.push(err),
+        }
+        if volumes {
+            match self.prune_volumes(filters.volumes()).await {
+                Ok(res) => {
+                    report.volumes_deleted = res.volumes_deleted;
+                    report.space_reclaimed += res.space_reclaimed;
+                }
+                Err(err) => report.errors.push(err),
+            }
+        }
+        if report.errors.len() == steps {
+            return Err(report.errors.remove(0));
+        }
+        Ok(report)
+    }
 }
 
 impl HaveHttpClient for Docker {
@@ -1555,69 +3559,903 @@ mod tests {
     use log::trace;
     use rand::Rng;
 
-    async fn read_bytes_stream_to_end(src: BoxStream<'static, Result<Bytes, DwError>>) -> Vec<u8> {
-        use futures::stream::TryStreamExt;
-        let src = src.map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err));
-        let mut aread = tokio_util::io::StreamReader::new(src);
-        let mut buf = vec![];
-        use tokio::io::AsyncReadExt;
-        aread.read_to_end(&mut buf).await.unwrap();
-        buf
+    async fn read_bytes_stream_to_end(src: BoxStream<'static, Result<Bytes, DwError>>) -> Vec<u8> {
+        use futures::stream::TryStreamExt;
+        let src = src.map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err));
+        let mut aread = tokio_util::io::StreamReader::new(src);
+        let mut buf = vec![];
+        use tokio::io::AsyncReadExt;
+        aread.read_to_end(&mut buf).await.unwrap();
+        buf
+    }
+
+    async fn read_frame_all(
+        mut src: BoxStream<'static, Result<AttachResponseFrame, DwError>>,
+    ) -> Result<(Vec<u8>, Vec<u8>, Vec<u8>), DwError> {
+        let mut stdout_buf = vec![];
+        let mut stdin_buf = vec![];
+        let mut stderr_buf = vec![];
+        use futures::stream::StreamExt;
+        while let Some(mut stdio) = src.next().await.transpose()? {
+            match stdio.type_ {
+                ContainerStdioType::Stdin => {
+                    stdin_buf.append(&mut stdio.frame);
+                }
+                ContainerStdioType::Stdout => {
+                    stdout_buf.append(&mut stdio.frame);
+                }
+                ContainerStdioType::Stderr => {
+                    stderr_buf.append(&mut stdio.frame);
+                }
+            }
+        }
+        Ok((stdin_buf, stdout_buf, stderr_buf))
+    }
+
+    async fn read_file(path: PathBuf) -> Vec<u8> {
+        let mut file = tokio::fs::File::open(path).await.unwrap();
+        let mut buf = vec![];
+        use tokio::io::AsyncReadExt;
+        file.read_to_end(&mut buf).await.unwrap();
+        buf
+    }
+
+    #[test]
+    fn test_registry_auth_header() {
+        let credential = Credential::with_password(UserPassword::new(
+            "user".to_owned(),
+            "pass".to_owned(),
+            "user@example.com".to_owned(),
+            "localhost:5000".to_owned(),
+        ));
+        let header = registry_auth_header(&credential).unwrap();
+        let json = general_purpose::STANDARD
+            .decode(header.to_str().unwrap())
+            .unwrap();
+        assert_eq!(
+            serde_json::from_slice::<Credential>(&json).unwrap(),
+            credential
+        );
+    }
+
+    #[test]
+    fn test_container_status_of_event() {
+        use ContainerStatus::*;
+        let statuses: Vec<_> = CONTAINER_STATE_EVENTS
+            .iter()
+            .map(|action| (*action, container_status_of_event(action)))
+            .collect();
+        assert_eq!(
+            statuses,
+            [
+                ("create", Some(Created)),
+                ("start", Some(Running)),
+                ("restart", Some(Running)),
+                ("pause", Some(Paused)),
+                ("unpause", Some(Running)),
+                ("die", Some(Exited)),
+                ("destroy", None),
+            ]
+        );
+        for action in ["stop", "kill", "oom", "exec_create"] {
+            assert_eq!(container_status_of_event(action), None, "{action}");
+        }
+    }
+
+    #[test]
+    fn test_attach_params() {
+        assert_eq!(
+            attach_params(Some("ctrl-p,ctrl-q"), true, true, false, true, false).unwrap(),
+            "detachKeys=ctrl-p%2Cctrl-q&logs=true&stream=true&stdin=false&stdout=true&stderr=false"
+        );
+        assert_eq!(
+            attach_params(None, false, true, true, true, true).unwrap(),
+            "logs=false&stream=true&stdin=true&stdout=true&stderr=true"
+        );
+        assert!(matches!(
+            attach_params(Some("ctrl-1"), false, true, true, true, true),
+            Err(DwError::InvalidDetachKeys { .. })
+        ));
+    }
+
+    #[test]
+    fn test_split_image_reference() {
+        assert_eq!(split_image_reference("alpine"), ("alpine", "latest"));
+        assert_eq!(split_image_reference("alpine:3.9"), ("alpine", "3.9"));
+        assert_eq!(
+            split_image_reference("localhost:5000/foo/bar"),
+            ("localhost:5000/foo/bar", "latest")
+        );
+        assert_eq!(
+            split_image_reference("localhost:5000/foo/bar:v1"),
+            ("localhost:5000/foo/bar", "v1")
+        );
+        assert_eq!(
+            split_image_reference("alpine@sha256:769fdd"),
+            ("alpine", "sha256:769fdd")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_attach_invalid_detach_keys() {
+        // rejected before connecting, so no daemon is needed
+        let docker = Docker::connect_with_unix("/nonexistent/docker.sock").unwrap();
+        let res = docker
+            .attach_container("foo", Some("ctrl-1"), false, true, false, true, true)
+            .await;
+        assert!(
+            matches!(&res, Err(DwError::InvalidDetachKeys { keys, .. }) if keys == "ctrl-1"),
+            "{:?}",
+            res.err()
+        );
+        let res = docker
+            .attach_container_with_stdin("foo", Some("ctrl-1"), false)
+            .await;
+        assert!(
+            matches!(&res, Err(DwError::InvalidDetachKeys { keys, .. }) if keys == "ctrl-1"),
+            "{:?}",
+            res.err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_build_image_remote_without_remote() {
+        // rejected before connecting, so no daemon is needed
+        let docker = Docker::connect_with_unix("/nonexistent/docker.sock").unwrap();
+        let res = docker
+            .build_image_remote(ContainerBuildOptions::default())
+            .await;
+        assert!(matches!(res, Err(DwError::MissingRemote)));
+    }
+
+    #[test]
+    fn test_is_connection_error() {
+        use std::io::{Error, ErrorKind};
+        assert!(is_connection_error(
+            &Error::from(ErrorKind::ConnectionReset).into()
+        ));
+        assert!(is_connection_error(
+            &Error::new(ErrorKind::Other, Error::from(ErrorKind::ConnectionRefused)).into()
+        ));
+        assert!(!is_connection_error(
+            &Error::from(ErrorKind::NotFound).into()
+        ));
+        assert!(!is_connection_error(&DwError::Unknown {
+            message: "oops".to_owned()
+        }));
+    }
+
+    #[test]
+    fn test_label_change() {
+        assert_eq!(
+            label_change("org.example.version", "1.0"),
+            r#"LABEL "org.example.version"="1.0""#
+        );
+        assert_eq!(
+            label_change("description", r#"say "hi" \ bye"#),
+            r#"LABEL "description"="say \"hi\" \\ bye""#
+        );
+    }
+
+    #[test]
+    fn test_unpack_archive() {
+        fn archive(paths: &[&[u8]]) -> Vec<u8> {
+            let mut builder = tar::Builder::new(Vec::new());
+            for path in paths {
+                let mut header = tar::Header::new_gnu();
+                header.as_old_mut().name[..path.len()].copy_from_slice(path);
+                header.set_size(2);
+                header.set_mode(0o644);
+                header.set_cksum();
+                builder.append(&header, &b"ok"[..]).unwrap();
+            }
+            builder.into_inner().unwrap()
+        }
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let dest = std::env::temp_dir().join(format!("dockworker-test-unpack-{nanos}"));
+
+        let ar = archive(&[b"etc/hostname", b"./bin/sh"]);
+        unpack_archive(&ar[..], &dest.join("rootfs")).unwrap();
+        assert_eq!(
+            std::fs::read(dest.join("rootfs/etc/hostname")).unwrap(),
+            b"ok"
+        );
+        assert_eq!(std::fs::read(dest.join("rootfs/bin/sh")).unwrap(), b"ok");
+
+        let ar = archive(&[b"etc/hostname", b"../evil"]);
+        assert!(unpack_archive(&ar[..], &dest.join("rootfs")).is_err());
+        assert!(!dest.join("evil").exists());
+
+        std::fs::remove_dir_all(dest).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_into_tar_files() {
+        use futures::stream::{StreamExt, TryStreamExt};
+        let long_name = format!("dir/{}", "x".repeat(120));
+        let mut builder = tar::Builder::new(Vec::new());
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(tar::EntryType::Directory);
+        header.set_size(0);
+        builder.append_data(&mut header, "dir", &[][..]).unwrap();
+        for (path, data) in [("dir/a.txt", &b"hello"[..]), (&long_name, &[7; 1000][..])] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(data.len() as u64);
+            builder.append_data(&mut header, path, data).unwrap();
+        }
+        let archive = builder.into_inner().unwrap();
+        // split into chunks unaligned to tar blocks
+        let chunks = archive
+            .chunks(100)
+            .map(|chunk| Ok(Bytes::copy_from_slice(chunk)))
+            .collect::<Vec<_>>();
+        let files = into_tar_files(futures::stream::iter(chunks).boxed())
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(
+            files,
+            vec![
+                (PathBuf::from("dir/a.txt"), Bytes::from_static(b"hello")),
+                (PathBuf::from(long_name), Bytes::from(vec![7; 1000])),
+            ]
+        );
+
+        let truncated = Bytes::copy_from_slice(&archive[..600]);
+        let res = into_tar_files(futures::stream::iter(vec![Ok(truncated)]).boxed())
+            .try_collect::<Vec<_>>()
+            .await;
+        assert!(res.is_err());
+
+        // a corrupt header with a size close to `u64::MAX`
+        let mut header = tar::Header::new_gnu();
+        header.set_path("big").unwrap();
+        header.set_size(u64::MAX);
+        header.set_cksum();
+        let corrupt = Bytes::copy_from_slice(header.as_bytes());
+        let res = into_tar_files(futures::stream::iter(vec![Ok(corrupt)]).boxed())
+            .try_collect::<Vec<_>>()
+            .await;
+        assert!(
+            matches!(&res, Err(DwError::Unknown { message }) if message.contains("entry size")),
+            "{res:?}"
+        );
+    }
+
+    #[test]
+    fn test_split_log_timestamp() {
+        let (timestamp, message) =
+            split_log_timestamp("2019-03-01T12:34:56.123456789Z hello  world").unwrap();
+        assert_eq!(timestamp.timestamp_subsec_nanos(), 123456789);
+        assert_eq!(
+            timestamp.to_rfc3339(),
+            "2019-03-01T12:34:56.123456789+00:00"
+        );
+        assert_eq!(message, "hello  world");
+        let (_, message) = split_log_timestamp("2019-03-01T12:34:56Z").unwrap();
+        assert_eq!(message, "");
+        assert!(split_log_timestamp("hello world").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_into_log_lines_lossy() {
+        use futures::stream::TryStreamExt;
+        let body = hyper::Body::from(b"caf\xe9\r\nok\nlast".to_vec());
+        let lines: Vec<String> = into_log_lines(body, true)
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(lines, vec!["caf\u{fffd}", "ok", "last"]);
+
+        let frames = [
+            (ContainerStdioType::Stdout, b"caf\xe9\n".to_vec()),
+            (ContainerStdioType::Stderr, b"ok\n".to_vec()),
+        ]
+        .into_iter()
+        .flat_map(|(type_, frame)| AttachResponseFrame { type_, frame }.to_stdcopy())
+        .collect::<Vec<u8>>();
+        let lines: Vec<String> = into_log_lines(hyper::Body::from(frames), false)
+            .await
+            .unwrap()
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(lines, vec!["caf\u{fffd}", "ok"]);
+    }
+
+    #[tokio::test]
+    async fn test_ping() {
+        let docker = Docker::connect_with_defaults().unwrap();
+        docker.ping().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_system_info() {
+        let docker = Docker::connect_with_defaults().unwrap();
+        docker.system_info().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_version() {
+        let docker = Docker::connect_with_defaults().unwrap();
+        docker.version().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_api_version() {
+        let docker = Docker::connect_with_defaults().unwrap();
+        let version = docker.version().await.unwrap();
+        assert_eq!(docker.api_version().await.unwrap(), version.ApiVersion);
+    }
+
+    #[tokio::test]
+    async fn test_events() {
+        let docker = Docker::connect_with_defaults().unwrap();
+        let _ = docker.events(None, None, None).await.unwrap();
+    }
+
+    #[test]
+    fn test_repo_digest_of() {
+        let digests = vec![
+            "alpine@sha256:aaaa".to_owned(),
+            "localhost:5000/alpine@sha256:bbbb".to_owned(),
+        ];
+        assert_eq!(
+            repo_digest_of(&digests, "alpine"),
+            Some("alpine@sha256:aaaa")
+        );
+        assert_eq!(
+            repo_digest_of(&digests, "docker.io/library/alpine"),
+            Some("alpine@sha256:aaaa")
+        );
+        assert_eq!(
+            repo_digest_of(&digests, "localhost:5000/alpine"),
+            Some("localhost:5000/alpine@sha256:bbbb")
+        );
+        assert_eq!(repo_digest_of(&digests, "debian"), None);
+    }
+
+    #[test]
+    fn test_futures_are_send() {
+        fn assert_send<T: Send>(_: T) {}
+        let docker = Docker::connect_with_http("http://127.0.0.1:2375").unwrap();
+        docker.set_credential(Credential::with_password(UserPassword::new(
+            "user".to_owned(),
+            "pass".to_owned(),
+            "user@example.com".to_owned(),
+            "localhost:5000".to_owned(),
+        )));
+        assert_send(docker.create_image("alpine", "latest"));
+        assert_send(docker.push_image("alpine", "latest"));
+        assert_send(docker.list_containers(None, None, None, ContainerFilters::new()));
+        assert_send(docker.stats_resilient("foo"));
+    }
+
+    /// Socket path of the fake daemon `name`
+    fn fake_daemon_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "dockworker-test-{}-{}.sock",
+            name,
+            std::process::id()
+        ))
+    }
+
+    /// Fake daemon served by a task, stopped and removed when dropped
+    struct FakeDaemon<T> {
+        path: PathBuf,
+        server: Option<tokio::task::JoinHandle<T>>,
+    }
+
+    impl<T> FakeDaemon<T> {
+        /// Wait for the server task to finish
+        async fn join(mut self) -> T {
+            self.server.take().unwrap().await.unwrap()
+        }
+    }
+
+    impl<T> Drop for FakeDaemon<T> {
+        fn drop(&mut self) {
+            if let Some(server) = self.server.take() {
+                server.abort();
+            }
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+
+    /// Serve `handler` on the unix socket of the fake daemon `name`
+    ///
+    /// Returns the socket path and a guard which stops the daemon and removes the socket.
+    fn fake_daemon<F, Fut>(name: &str, handler: F) -> (PathBuf, FakeDaemon<Fut::Output>)
+    where
+        F: FnOnce(tokio::net::UnixListener) -> Fut,
+        Fut: std::future::Future + Send + 'static,
+        Fut::Output: Send + 'static,
+    {
+        let path = fake_daemon_path(name);
+        let _ = std::fs::remove_file(&path);
+        let listener = tokio::net::UnixListener::bind(&path).unwrap();
+        let server = tokio::spawn(handler(listener));
+        let daemon = FakeDaemon {
+            path: path.clone(),
+            server: Some(server),
+        };
+        (path, daemon)
+    }
+
+    /// Read the head of a request, returning the request line
+    async fn read_request_head(conn: &mut tokio::io::BufReader<tokio::net::UnixStream>) -> String {
+        use tokio::io::AsyncBufReadExt;
+        let mut request_line = String::new();
+        conn.read_line(&mut request_line).await.unwrap();
+        let mut line = String::new();
+        while line != "\r\n" {
+            line.clear();
+            // the client closed the connection before the end of the head
+            if conn.read_line(&mut line).await.unwrap() == 0 {
+                break;
+            }
+        }
+        request_line.trim_end().to_owned()
+    }
+
+    #[tokio::test]
+    async fn test_with_deadline() {
+        // a daemon which accepts connections but never responds
+        let (path, _daemon) = fake_daemon("deadline", |listener| async move {
+            use tokio::io::AsyncWriteExt;
+            let mut conns = Vec::new();
+            while let Ok((mut conn, _)) = listener.accept().await {
+                // respond to the second request with headers only
+                if conns.len() == 1 {
+                    let head = "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n";
+                    conn.write_all(head.as_bytes()).await.unwrap();
+                }
+                conns.push(conn);
+            }
+        });
+        let docker = Docker::connect_with_unix(path.to_str().unwrap()).unwrap();
+        let deadline = tokio::time::Instant::now() + Duration::from_millis(100);
+        let res = docker.with_deadline(deadline).ping().await;
+        assert!(matches!(res, Err(DwError::Timeout)), "{res:?}");
+
+        use futures::stream::StreamExt;
+        let deadline = tokio::time::Instant::now() + Duration::from_millis(100);
+        let mut stats = docker
+            .with_deadline(deadline)
+            .stats("foo", Some(true), None)
+            .await
+            .unwrap();
+        let res = stats.next().await;
+        assert!(matches!(res, Some(Err(DwError::Timeout))), "{res:?}");
+    }
+
+    #[tokio::test]
+    async fn test_attach_close_stdin() {
+        // a daemon which echoes stdin as stdout after the stdin is closed
+        let (path, daemon) = fake_daemon("attach", |listener| async move {
+            use tokio::io::{AsyncReadExt, AsyncWriteExt};
+            let (conn, _) = listener.accept().await.unwrap();
+            let mut conn = tokio::io::BufReader::new(conn);
+            read_request_head(&mut conn).await;
+            let head = "HTTP/1.1 101 UPGRADED\r\n\
+                        Content-Type: application/vnd.docker.raw-stream\r\n\
+                        Connection: Upgrade\r\n\
+                        Upgrade: tcp\r\n\r\n";
+            conn.write_all(head.as_bytes()).await.unwrap();
+            let mut stdin = Vec::new();
+            conn.read_to_end(&mut stdin).await.unwrap();
+            let frame = AttachResponseFrame {
+                type_: ContainerStdioType::Stdout,
+                frame: stdin,
+            };
+            conn.write_all(&frame.to_stdcopy()).await.unwrap();
+        });
+
+        use futures::stream::TryStreamExt;
+        let docker = Docker::connect_with_unix(path.to_str().unwrap()).unwrap();
+        let (mut stdin, stdout) = docker
+            .attach_container_with_stdin("foo", None, false)
+            .await
+            .unwrap();
+        stdin.write_all(b"hello ").await.unwrap();
+        stdin.write_all(b"world").await.unwrap();
+        stdin.close_stdin().await.unwrap();
+        let frames = stdout.try_collect::<Vec<_>>().await.unwrap();
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0].type_, ContainerStdioType::Stdout);
+        assert_eq!(frames[0].frame, b"hello world");
+        daemon.join().await;
+    }
+
+    #[tokio::test]
+    async fn test_attach_broadcast() {
+        // a daemon which sends 2 frames when `start` is notified
+        let start = Arc::new(tokio::sync::Notify::new());
+        let (path, daemon) = fake_daemon("broadcast", {
+            let start = start.clone();
+            |listener| async move {
+                use tokio::io::AsyncWriteExt;
+                for _ in 0..2 {
+                    let (conn, _) = listener.accept().await.unwrap();
+                    let mut conn = tokio::io::BufReader::new(conn);
+                    read_request_head(&mut conn).await;
+                    let head = "HTTP/1.1 200 OK\r\n\
+                                Content-Type: application/vnd.docker.raw-stream\r\n\r\n";
+                    conn.write_all(head.as_bytes()).await.unwrap();
+                    conn.flush().await.unwrap();
+                    start.notified().await;
+                    for (type_, frame) in [
+                        (ContainerStdioType::Stdout, b"out".to_vec()),
+                        (ContainerStdioType::Stderr, b"err".to_vec()),
+                    ] {
+                        let frame = AttachResponseFrame { type_, frame };
+                        conn.write_all(&frame.to_stdcopy()).await.unwrap();
+                    }
+                }
+            }
+        });
+
+        use futures::stream::{StreamExt, TryStreamExt};
+        let docker = Docker::connect_with_unix(path.to_str().unwrap()).unwrap();
+        let (first, broadcast) = docker
+            .attach_container_broadcast("foo", None, false, 16)
+            .await
+            .unwrap();
+        let second = broadcast.subscribe();
+        drop(broadcast);
+        start.notify_one();
+        let first = first.try_collect::<Vec<_>>().await.unwrap();
+        let second = second.try_collect::<Vec<_>>().await.unwrap();
+        assert_eq!(first, second);
+        assert_eq!(first.len(), 2);
+        assert_eq!(first[0].frame, b"out");
+        assert_eq!(first[1].type_, ContainerStdioType::Stderr);
+
+        // a subscription not read until the end skips the oldest frame
+        let (first, broadcast) = docker
+            .attach_container_broadcast("foo", None, false, 1)
+            .await
+            .unwrap();
+        let second = broadcast.subscribe();
+        drop(broadcast);
+        start.notify_one();
+        let first = first.collect::<Vec<_>>().await;
+        assert_eq!(first.last().unwrap().as_ref().unwrap().frame, b"err");
+        let second = second.collect::<Vec<_>>().await;
+        assert_eq!(second.len(), 2);
+        assert!(matches!(second[0], Err(DwError::Lagged { skipped: 1 })));
+        assert_eq!(second[1].as_ref().unwrap().frame, b"err");
+        daemon.join().await;
+    }
+
+    /// Temporary file of `len` bytes, each of which is its offset modulo 251
+    fn temp_file_of_len(name: &str, len: usize) -> (PathBuf, Vec<u8>) {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let path = std::env::temp_dir().join(format!("dockworker-test-{name}-{nanos}"));
+        let content: Vec<u8> = (0..len).map(|i| (i % 251) as u8).collect();
+        std::fs::write(&path, &content).unwrap();
+        (path, content)
+    }
+
+    #[tokio::test]
+    async fn test_file_body_with_progress() {
+        let (path, content) = temp_file_of_len("progress", 3 * 8192 + 100);
+        let progress = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let body = file_body_with_progress(&path, {
+            let progress = progress.clone();
+            move |sent| progress.lock().unwrap().push(sent)
+        })
+        .await
+        .unwrap();
+        let body = hyper::body::to_bytes(body).await.unwrap();
+        assert_eq!(&body[..], &content[..]);
+        let progress = progress.lock().unwrap();
+        assert!(progress.len() > 1, "{progress:?}");
+        assert!(progress.windows(2).all(|w| w[0] < w[1]), "{progress:?}");
+        assert_eq!(progress.last(), Some(&(content.len() as u64)));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_put_file_with_progress() {
+        // a daemon which records the request line and the chunked body
+        let (path, daemon) = fake_daemon("put", |listener| async move {
+            use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt};
+            let (conn, _) = listener.accept().await.unwrap();
+            let mut conn = tokio::io::BufReader::new(conn);
+            let request_line = read_request_head(&mut conn).await;
+            let mut body = Vec::new();
+            loop {
+                let mut size = String::new();
+                conn.read_line(&mut size).await.unwrap();
+                let size = usize::from_str_radix(size.trim_end(), 16).unwrap();
+                let mut chunk = vec![0; size + 2];
+                conn.read_exact(&mut chunk).await.unwrap();
+                assert_eq!(chunk.split_off(size), b"\r\n");
+                if size == 0 {
+                    break;
+                }
+                body.extend_from_slice(&chunk);
+            }
+            let res = "HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n";
+            conn.write_all(res.as_bytes()).await.unwrap();
+            (request_line, body)
+        });
+
+        let (src, content) = temp_file_of_len("put", 3 * 8192 + 100);
+        let docker = Docker::connect_with_unix(path.to_str().unwrap()).unwrap();
+        let sent = Arc::new(std::sync::Mutex::new(0));
+        docker
+            .put_file_with_progress("foo", &src, Path::new("/tmp"), false, {
+                let sent = sent.clone();
+                move |n| *sent.lock().unwrap() = n
+            })
+            .await
+            .unwrap();
+        let (request_line, body) = daemon.join().await;
+        assert!(
+            request_line.starts_with("PUT ")
+                && request_line.ends_with(
+                    "/containers/foo/archive?path=%2Ftmp&noOverwriteDirNonDir=false HTTP/1.1"
+                ),
+            "{request_line}"
+        );
+        assert_eq!(body, content);
+        assert_eq!(*sent.lock().unwrap(), content.len() as u64);
+        std::fs::remove_file(&src).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_list_containers_with_stats() {
+        // a daemon listing 2 running containers, one of which is removed before its stats
+        let (path, _daemon) = fake_daemon("stats", |listener| async move {
+            use tokio::io::AsyncWriteExt;
+            let mut containers: Vec<serde_json::Value> =
+                serde_json::from_str(include_str!("fixtures/containers_response.json")).unwrap();
+            containers[0]["State"] = "running".into();
+            containers.push(containers[0].clone());
+            containers[0]["Id"] = "gone".into();
+            containers[1]["Id"] = "alive".into();
+            let stats: serde_json::Value =
+                serde_json::from_str(include_str!("fixtures/stats_suspend.json")).unwrap();
+            loop {
+                let (conn, _) = listener.accept().await.unwrap();
+                let mut conn = tokio::io::BufReader::new(conn);
+                let request_line = read_request_head(&mut conn).await;
+                let (status, body) = if request_line.contains("/containers/json") {
+                    ("200 OK", serde_json::to_string(&containers).unwrap())
+                } else if request_line.contains("/containers/gone/stats") {
+                    (
+                        "404 Not Found",
+                        r#"{"message":"No such container: gone"}"#.to_owned(),
+                    )
+                } else {
+                    ("200 OK", stats.to_string())
+                };
+                let res = format!(
+                    "HTTP/1.1 {}\r\nContent-Type: application/json\r\n\
+                     Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                conn.write_all(res.as_bytes()).await.unwrap();
+            }
+        });
+
+        let docker = Docker::connect_with_unix(path.to_str().unwrap()).unwrap();
+        let containers = docker
+            .list_containers_with_stats(ContainerFilters::new())
+            .await
+            .unwrap();
+        assert_eq!(containers.len(), 1);
+        assert_eq!(containers[0].0.Id, "alive");
     }
 
-    async fn read_frame_all(
-        mut src: BoxStream<'static, Result<AttachResponseFrame, DwError>>,
-    ) -> Result<(Vec<u8>, Vec<u8>, Vec<u8>), DwError> {
-        let mut stdout_buf = vec![];
-        let mut stdin_buf = vec![];
-        let mut stderr_buf = vec![];
-        use futures::stream::StreamExt;
-        while let Some(mut stdio) = src.next().await.transpose()? {
-            match stdio.type_ {
-                ContainerStdioType::Stdin => {
-                    stdin_buf.append(&mut stdio.frame);
-                }
-                ContainerStdioType::Stdout => {
-                    stdout_buf.append(&mut stdio.frame);
-                }
-                ContainerStdioType::Stderr => {
-                    stderr_buf.append(&mut stdio.frame);
-                }
+    #[tokio::test]
+    async fn test_kill_container_named() {
+        // a daemon which records the request lines
+        let (path, daemon) = fake_daemon("kill", |listener| async move {
+            use tokio::io::AsyncWriteExt;
+            let mut request_lines = Vec::new();
+            for _ in 0..2 {
+                let (conn, _) = listener.accept().await.unwrap();
+                let mut conn = tokio::io::BufReader::new(conn);
+                request_lines.push(read_request_head(&mut conn).await);
+                let res = "HTTP/1.1 204 No Content\r\nConnection: close\r\n\r\n";
+                conn.write_all(res.as_bytes()).await.unwrap();
             }
-        }
-        Ok((stdin_buf, stdout_buf, stderr_buf))
+            request_lines
+        });
+
+        let docker = Docker::connect_with_unix(path.to_str().unwrap()).unwrap();
+        docker.kill_container_named("foo", "SIGUSR1").await.unwrap();
+        docker
+            .kill_container("foo", Signal::from_c_int(15).unwrap())
+            .await
+            .unwrap();
+        let request_lines = daemon.join().await;
+        assert!(
+            request_lines[0].ends_with("/containers/foo/kill?signal=SIGUSR1 HTTP/1.1"),
+            "{request_lines:?}"
+        );
+        assert!(
+            request_lines[1].ends_with("/containers/foo/kill?signal=15 HTTP/1.1"),
+            "{request_lines:?}"
+        );
     }
 
-    async fn read_file(path: PathBuf) -> Vec<u8> {
-        let mut file = tokio::fs::File::open(path).await.unwrap();
-        let mut buf = vec![];
-        use tokio::io::AsyncReadExt;
-        file.read_to_end(&mut buf).await.unwrap();
-        buf
+    /// Fake daemon answering each prune api with `status` and `body`, or 500 if absent
+    fn fake_prune_daemon(
+        name: &str,
+        responses: &'static [(&'static str, &'static str)],
+    ) -> (PathBuf, FakeDaemon<()>) {
+        fake_daemon(name, move |listener| async move {
+            use tokio::io::AsyncWriteExt;
+            while let Ok((conn, _)) = listener.accept().await {
+                let mut conn = tokio::io::BufReader::new(conn);
+                let request_line = read_request_head(&mut conn).await;
+                let (status, body) = responses
+                    .iter()
+                    .find(|(path, _)| request_line.contains(path))
+                    .map(|(_, body)| ("200 OK", *body))
+                    .unwrap_or(("500 Internal Server Error", r#"{"message":"prune failed"}"#));
+                let res = format!(
+                    "HTTP/1.1 {}\r\nContent-Type: application/json\r\n\
+                     Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                conn.write_all(res.as_bytes()).await.unwrap();
+            }
+        })
     }
 
     #[tokio::test]
-    async fn test_ping() {
-        let docker = Docker::connect_with_defaults().unwrap();
-        docker.ping().await.unwrap();
+    async fn test_system_prune_partial_failure() {
+        // a daemon failing to prune networks
+        let (path, _daemon) = fake_prune_daemon(
+            "prune-partial",
+            &[
+                (
+                    "/containers/prune",
+                    r#"{"ContainersDeleted":["foo"],"SpaceReclaimed":10}"#,
+                ),
+                (
+                    "/images/prune",
+                    r#"{"ImagesDeleted":[{"Deleted":"sha256:bar"}],"SpaceReclaimed":5}"#,
+                ),
+            ],
+        );
+        let docker = Docker::connect_with_unix(path.to_str().unwrap()).unwrap();
+        let report = docker
+            .system_prune(false, PruneFilters::default())
+            .await
+            .unwrap();
+        assert_eq!(report.containers_deleted, ["foo".to_owned()]);
+        assert_eq!(
+            report.images_deleted,
+            [RemovedImage::Deleted("sha256:bar".to_owned())]
+        );
+        assert!(report.networks_deleted.is_empty());
+        assert_eq!(report.space_reclaimed, 15);
+        assert_eq!(report.errors.len(), 1, "{:?}", report.errors);
+        assert!(
+            matches!(&report.errors[0], DwError::Docker(err) if err.message == "prune failed"),
+            "{:?}",
+            report.errors
+        );
     }
 
     #[tokio::test]
-    async fn test_system_info() {
-        let docker = Docker::connect_with_defaults().unwrap();
-        docker.system_info().await.unwrap();
+    async fn test_system_prune_total_failure() {
+        let (path, _daemon) = fake_prune_daemon("prune-total", &[]);
+        let docker = Docker::connect_with_unix(path.to_str().unwrap()).unwrap();
+        let res = docker.system_prune(true, PruneFilters::default()).await;
+        assert!(
+            matches!(&res, Err(DwError::Docker(err)) if err.message == "prune failed"),
+            "{res:?}"
+        );
     }
 
     #[tokio::test]
-    async fn test_version() {
-        let docker = Docker::connect_with_defaults().unwrap();
-        docker.version().await.unwrap();
+    async fn test_api_version_fake() {
+        // a daemon answering the first ping with `Api-Version` and the second without it
+        let (path, daemon) = fake_daemon("api-version", |listener| async move {
+            use tokio::io::AsyncWriteExt;
+            let mut request_lines = Vec::new();
+            for res in [
+                "HTTP/1.1 200 OK\r\nApi-Version: 1.41\r\n\
+                 Content-Length: 2\r\nConnection: close\r\n\r\n",
+                "HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\n",
+            ] {
+                let (conn, _) = listener.accept().await.unwrap();
+                let mut conn = tokio::io::BufReader::new(conn);
+                request_lines.push(read_request_head(&mut conn).await);
+                conn.write_all(res.as_bytes()).await.unwrap();
+            }
+            request_lines
+        });
+
+        let docker = Docker::connect_with_unix(path.to_str().unwrap()).unwrap();
+        assert_eq!(docker.api_version().await.unwrap(), "1.41");
+        let res = docker.api_version().await;
+        assert!(matches!(res, Err(DwError::Unknown { .. })), "{res:?}");
+        let request_lines = daemon.join().await;
+        assert!(
+            request_lines
+                .iter()
+                .all(|line| line.starts_with("HEAD ") && line.ends_with("/_ping HTTP/1.1")),
+            "{request_lines:?}"
+        );
     }
 
     #[tokio::test]
-    async fn test_events() {
+    async fn test_wait_until_ready() {
+        let path = fake_daemon_path("ready");
+        let _ = std::fs::remove_file(&path);
+        let docker = Docker::connect_with_unix(path.to_str().unwrap()).unwrap();
+        let res = docker.wait_until_ready(Duration::from_millis(200)).await;
+        assert!(matches!(res, Err(DwError::Timeout)), "{res:?}");
+
+        // a daemon which starts listening a while later
+        let ready = tokio::spawn({
+            let docker = docker.clone();
+            async move { docker.wait_until_ready(Duration::from_secs(10)).await }
+        });
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        let (_, _daemon) = fake_daemon("ready", |listener| async move {
+            use tokio::io::AsyncWriteExt;
+            while let Ok((conn, _)) = listener.accept().await {
+                let mut conn = tokio::io::BufReader::new(conn);
+                read_request_head(&mut conn).await;
+                let res = "HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nOK";
+                conn.write_all(res.as_bytes()).await.unwrap();
+            }
+        });
+        ready.await.unwrap().unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_requests() {
         let docker = Docker::connect_with_defaults().unwrap();
-        let _ = docker.events(None, None, None).await.unwrap();
+        let tasks = (0..16).map(|i| {
+            let docker = docker.clone();
+            tokio::spawn(async move {
+                if i % 4 == 0 {
+                    // contend the credential with the other tasks;
+                    // nothing is pulled, so it is never sent to a registry
+                    docker.set_credential(Credential::with_password(UserPassword::new(
+                        "user".to_owned(),
+                        "pass".to_owned(),
+                        "user@example.com".to_owned(),
+                        "registry.example.com".to_owned(),
+                    )));
+                } else if i % 2 == 0 {
+                    docker.headers_with_registry_auth().unwrap();
+                }
+                docker
+                    .list_containers(Some(true), None, None, ContainerFilters::new())
+                    .await
+                    .unwrap();
+            })
+        });
+        let results =
+            tokio::time::timeout(Duration::from_secs(60), futures::future::join_all(tasks))
+                .await
+                .expect("deadlock in concurrent requests");
+        for res in results {
+            res.unwrap();
+        }
     }
 
     async fn double_stop_container(docker: &Docker, container: &str) {
@@ -1634,6 +4472,24 @@ mod tests {
             .unwrap();
     }
 
+    async fn pause_container(docker: &Docker, container: &str) {
+        docker.start_container(container).await.unwrap();
+        let info = docker.container_info(container).await.unwrap();
+        assert_eq!(info.State.status_enum(), ContainerStatus::Running);
+        docker.pause_container(container).await.unwrap();
+        let info = docker.container_info(container).await.unwrap();
+        assert!(info.State.Paused);
+        assert_eq!(info.State.status_enum(), ContainerStatus::Paused);
+        docker.unpause_container(container).await.unwrap();
+        let info = docker.container_info(container).await.unwrap();
+        assert!(!info.State.Paused);
+        assert_eq!(info.State.status_enum(), ContainerStatus::Running);
+        docker
+            .stop_container(container, Duration::from_secs(10))
+            .await
+            .unwrap();
+    }
+
     async fn restart_container(docker: &Docker, container: &str) {
         docker.start_container(container).await.unwrap();
         docker
@@ -1650,6 +4506,24 @@ mod tests {
             .unwrap();
     }
 
+    async fn update_container(docker: &Docker, container: &str) {
+        let restart_policy = RestartPolicy::new("on-failure".to_owned(), 5);
+        let mut update = ContainerUpdateOptions::new();
+        update.restart_policy(restart_policy.clone());
+        docker.update_container(container, &update).await.unwrap();
+        let info = docker.container_info(container).await.unwrap();
+        assert_eq!(info.HostConfig.RestartPolicy, restart_policy);
+    }
+
+    async fn rename_container(docker: &Docker, container: &str, taken: &str) {
+        let renamed = format!("{container}_renamed");
+        docker.rename_container(container, &renamed).await.unwrap();
+        match docker.rename_container(&renamed, taken).await {
+            Err(DwError::Docker(err)) => assert_eq!(err.kind(), DockerErrorKind::Conflict),
+            res => panic!("unexpected result: {res:?}"),
+        }
+    }
+
     async fn stop_wait_container(docker: &Docker, container: &str) {
         docker.start_container(container).await.unwrap();
         docker.wait_container(container).await.unwrap();
@@ -1661,7 +4535,21 @@ mod tests {
             .await
             .unwrap();
         assert_eq!(res.name, "ls");
+        assert!(!res.is_dir());
         chrono::DateTime::parse_from_rfc3339(&res.mtime).unwrap();
+
+        assert!(docker
+            .file_exists(container, Path::new("/bin/ls"))
+            .await
+            .unwrap());
+        assert!(!docker
+            .file_exists(container, Path::new("/no/such/file"))
+            .await
+            .unwrap());
+        assert!(docker
+            .file_exists("dockworker_no_such_container", Path::new("/bin/ls"))
+            .await
+            .is_err());
     }
 
     async fn stats_container(docker: &Docker, container: &str) {
@@ -1767,6 +4655,18 @@ mod tests {
         println!("log_all\n{log_all}");
     }
 
+    /// The container prints `line 1` to `line 5`
+    async fn tail_logs_container(docker: &Docker, container: &str) {
+        docker.start_container(container).await.unwrap();
+        docker.wait_container(container).await.unwrap();
+
+        let tail = docker.tail_logs(container, 2).await.unwrap();
+        assert_eq!(tail, vec!["line 4", "line 5"]);
+        let tail = docker.tail_logs(container, 10).await.unwrap();
+        assert_eq!(tail.len(), 5);
+        assert_eq!(tail[0], "line 1");
+    }
+
     async fn connect_container(
         docker: &Docker,
         container_name: &str,
@@ -1862,6 +4762,73 @@ mod tests {
                 .await
                 .unwrap();
         }
+        println!("pause container");
+        {
+            let mut create = ContainerCreateOptions::new(image);
+            // keep it running so that it can be paused
+            create.cmd("sleep".to_string());
+            create.cmd("60".to_string());
+
+            let container = docker
+                .create_container(Some(&next_id()), &create)
+                .await
+                .unwrap();
+
+            pause_container(docker, &container.id).await;
+
+            docker
+                .remove_container(&container.id, None, None, None)
+                .await
+                .unwrap();
+        }
+        println!("update container");
+        {
+            let create = ContainerCreateOptions::new(image);
+
+            let container = docker
+                .create_container(Some(&next_id()), &create)
+                .await
+                .unwrap();
+
+            update_container(docker, &container.id).await;
+
+            docker
+                .remove_container(&container.id, None, None, None)
+                .await
+                .unwrap();
+        }
+        println!("rename container");
+        {
+            let create = ContainerCreateOptions::new(image);
+
+            let name = next_id();
+            let container = docker.create_container(Some(&name), &create).await.unwrap();
+            let taken = next_id();
+            let other = docker
+                .create_container(Some(&taken), &create)
+                .await
+                .unwrap();
+
+            rename_container(docker, &name, &taken).await;
+            assert_eq!(
+                docker.container_id_of(&taken).await.unwrap(),
+                Some(other.id.clone())
+            );
+            assert_eq!(
+                docker.container_name_of(&other.id).await.unwrap(),
+                Some(taken.clone())
+            );
+            assert_eq!(docker.container_id_of(&name).await.unwrap(), None);
+
+            docker
+                .remove_container(&container.id, None, None, None)
+                .await
+                .unwrap();
+            docker
+                .remove_container(&other.id, None, None, None)
+                .await
+                .unwrap();
+        }
         println!("auto remove container");
         {
             let mut create = ContainerCreateOptions::new(image);
@@ -1882,6 +4849,36 @@ mod tests {
                 .remove_container(&container.id, None, None, None)
                 .await;
             assert!(res.is_err());
+            docker
+                .wait_container_removed(&container.id, Duration::from_secs(10))
+                .await
+                .unwrap();
+        }
+        println!("container guard");
+        {
+            let create = ContainerCreateOptions::new(image);
+            let guard = docker
+                .create_container_guarded(Some(&next_id()), &create)
+                .await
+                .unwrap();
+            let id = guard.id().to_owned();
+            docker.container_info(&id).await.unwrap();
+            let raw = docker.container_info_raw(&id).await.unwrap();
+            assert_eq!(raw["Id"], id.as_str());
+            assert!(raw["GraphDriver"].is_object());
+            drop(guard);
+            docker
+                .wait_container_removed(&id, Duration::from_secs(10))
+                .await
+                .unwrap();
+
+            let guard = docker
+                .create_container_guarded(Some(&next_id()), &create)
+                .await
+                .unwrap();
+            let id = guard.id().to_owned();
+            guard.remove().await.unwrap();
+            assert!(docker.container_name_of(&id).await.unwrap().is_none());
         }
         println!("head file container");
         {
@@ -1966,6 +4963,24 @@ mod tests {
                 .await
                 .unwrap();
         }
+        println!("tail logs of container");
+        {
+            let mut create = ContainerCreateOptions::new(image);
+            create.entrypoint(vec!["sh".into(), "-c".into()]);
+            create.cmd("for i in 1 2 3 4 5; do echo line $i; done".to_string());
+
+            let container = docker
+                .create_container(Some(&next_id()), &create)
+                .await
+                .unwrap();
+
+            tail_logs_container(docker, &container.id).await;
+
+            docker
+                .remove_container(&container.id, None, None, None)
+                .await
+                .unwrap();
+        }
         println!("connect networks");
         {
             use std::collections::HashMap;
@@ -2016,6 +5031,61 @@ mod tests {
             "remove containers 'test_container_*'"
         );
         test_container(docker, &format!("{name}:{tag}")).await;
+        let size = docker.image_size(&format!("{name}:{tag}")).await.unwrap();
+        assert!(size > 0);
+        let layers = docker
+            .image_layer_sizes(&format!("{name}:{tag}"))
+            .await
+            .unwrap();
+        assert!(!layers.is_empty());
+        let id = docker.image_id(&format!("{name}:{tag}")).await.unwrap();
+        assert!(id.unwrap().id().starts_with("sha256:"));
+        assert_eq!(
+            docker
+                .image_id("dockworker-no-such-image:latest")
+                .await
+                .unwrap(),
+            None
+        );
+        let labels = HashMap::from([("org.example.test".to_owned(), "label".to_owned())]);
+        let labeled = docker
+            .label_image(&format!("{name}:{tag}"), labels)
+            .await
+            .unwrap();
+        let image = docker
+            .inspect_image(&format!("{name}:{tag}"))
+            .await
+            .unwrap();
+        assert_eq!(image.Id, labeled.id());
+        assert_eq!(image.Config.Labels["org.example.test"], "label");
+        assert_eq!(image.Config.Cmd, ["/bin/sh".to_owned()]);
+
+        let container = docker
+            .create_container(None, &ContainerCreateOptions::new(&format!("{name}:{tag}")))
+            .await
+            .unwrap();
+        let mut commit = CommitContainerOptions::new(&container.id);
+        commit
+            .repo("dockworker_test_commit")
+            .tag("v1")
+            .comment("committed")
+            .change("ENV DOCKWORKER=1");
+        let committed = docker.commit_container(&commit, None).await.unwrap();
+        docker
+            .remove_container(&container.id, None, Some(true), None)
+            .await
+            .unwrap();
+        let image = docker
+            .inspect_image("dockworker_test_commit:v1")
+            .await
+            .unwrap();
+        assert_eq!(image.Id, committed.id);
+        assert_eq!(image.Comment, "committed");
+        assert!(image.Config.Env.contains(&"DOCKWORKER=1".to_owned()));
+        docker
+            .remove_image("dockworker_test_commit:v1", None, None)
+            .await
+            .unwrap();
         let containers = docker
             .list_containers(Some(true), None, Some(true), filter)
             .await
@@ -2062,6 +5132,33 @@ mod tests {
         test_image(&docker, name, tag).await;
     }
 
+    #[tokio::test]
+    async fn test_tag_image() {
+        use futures::stream::TryStreamExt;
+        let docker = Docker::connect_with_defaults().unwrap();
+        let progress = docker.create_image("alpine", "3.9").await.unwrap();
+        progress.try_collect::<Vec<_>>().await.unwrap();
+
+        docker
+            .tag_image("alpine:3.9", "dockworker-tagtest", "foo", false)
+            .await
+            .unwrap();
+        let original = docker.inspect_image("alpine:3.9").await.unwrap();
+        let tagged = docker
+            .inspect_image("dockworker-tagtest:foo")
+            .await
+            .unwrap();
+        assert_eq!(tagged.Id, original.Id);
+        docker
+            .remove_image("dockworker-tagtest:foo", None, None)
+            .await
+            .unwrap();
+        assert!(docker
+            .inspect_image("dockworker-tagtest:foo")
+            .await
+            .is_err());
+    }
+
     #[cfg(feature = "experimental")]
     #[tokio::test]
     async fn test_container_checkpointing() {
@@ -2155,6 +5252,39 @@ mod tests {
         prune_networks(&docker).await;
     }
 
+    #[tokio::test]
+    async fn test_volumes() {
+        use crate::network::LabelFilter as F;
+        let docker = Docker::connect_with_defaults().unwrap();
+        let mut create = VolumeCreateOptions::new("dockworker_test_volume");
+        create.label("dockworker-test-volume", "1");
+        let volume = docker.create_volume(&create).await.unwrap();
+        assert_eq!(volume.Name, "dockworker_test_volume");
+        assert_eq!(
+            docker.inspect_volume(&volume.Name).await.unwrap().Labels,
+            create.labels
+        );
+
+        let mut filters = ListVolumeFilters::default();
+        filters
+            .dangling(true)
+            .label(F::with(&[("dockworker-test-volume", Some("1"))]));
+        let res = docker.list_volumes(filters).await.unwrap();
+        assert_eq!(res.Volumes.len(), 1);
+        assert_eq!(res.Volumes[0].Name, volume.Name);
+
+        let mut filters = PruneVolumeFilters::default();
+        filters
+            .all(true)
+            .label(F::with(&[("dockworker-test-volume", None)]));
+        let res = docker.prune_volumes(filters).await.unwrap();
+        assert_eq!(res.volumes_deleted, vec![volume.Name.clone()]);
+
+        let volume = docker.create_volume(&create).await.unwrap();
+        docker.remove_volume(&volume.Name, false).await.unwrap();
+        assert!(docker.inspect_volume(&volume.Name).await.is_err());
+    }
+
     async fn inspect_networks(docker: &Docker) {
         for network in &docker
             .list_networks(ListNetworkFilters::default())
@@ -2236,6 +5366,19 @@ mod tests {
             let res = docker.prune_networks(filter).await.unwrap();
             assert_eq!(&res.networks_deleted, &["nw_test_5".to_owned()]);
         }
+        println!("system prune");
+        {
+            docker
+                .create_network(Net::new("nw_test_7").label("test-network-7", "7"))
+                .await
+                .unwrap();
+            let mut filter = PruneFilters::default();
+            filter.label(F::with(&[("test-network-7", None)]));
+            let report = docker.system_prune(false, filter).await.unwrap();
+            assert!(report.errors.is_empty(), "{:?}", report.errors);
+            assert_eq!(&report.networks_deleted, &["nw_test_7".to_owned()]);
+            assert!(report.containers_deleted.is_empty());
+        }
         println!("prune network");
         {
             let res = docker.prune_networks(Prune::default()).await.unwrap();
@@ -2356,6 +5499,25 @@ mod tests {
         assert_eq!(exec_inspect.ExitCode, Some(0));
         assert_eq!(exec_inspect.Running, false);
 
+        let mut exec_config = CreateExecOptions::new();
+        exec_config.cmd("true".to_owned());
+        let exec_instance = docker
+            .exec_container(&container.id, &exec_config)
+            .await
+            .unwrap();
+        docker
+            .start_exec_detached(&exec_instance.id, &StartExecOptions::new())
+            .await
+            .unwrap();
+        let exec_inspect = loop {
+            let exec_inspect = docker.exec_inspect(&exec_instance.id).await.unwrap();
+            if !exec_inspect.Running {
+                break exec_inspect;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        };
+        assert_eq!(exec_inspect.ExitCode, Some(0));
+
         docker.wait_container(&container.id).await.unwrap();
         docker
             .remove_container(&container.id, None, None, None)
@@ -2384,23 +5546,18 @@ mod tests {
             .attach_container(&container.id, None, true, true, false, true, true)
             .await
             .unwrap();
-        let signals = [SIGHUP, SIGINT, SIGUSR1, SIGUSR2, SIGTERM];
-        let signalstrs = vec![
-            "HUP".to_string(),
-            "INT".to_string(),
-            "USR1".to_string(),
-            "USR2".to_string(),
-            "TERM".to_string(),
-        ];
+        let signals = ["SIGHUP", "SIGINT", "SIGUSR1", "SIGUSR2", "SIGTERM"]
+            .map(|name| Signal::from_name(name).unwrap());
+        let signalstrs = signals
+            .iter()
+            .map(|sig| sig.name().trim_start_matches("SIG").to_owned())
+            .collect::<Vec<_>>();
         let kill = async {
             // wait a moment
             tokio::time::sleep(std::time::Duration::from_secs(3)).await;
             for sig in signals {
                 trace!("cause signal: {:?}", sig);
-                docker
-                    .kill_container(&container.id, Signal::from(sig))
-                    .await
-                    .unwrap();
+                docker.kill_container(&container.id, sig).await.unwrap();
             }
         };
         let (ret, _) = futures::future::join(read_frame_all(res), kill).await;
@@ -1,6 +1,8 @@
 use crate::response;
+use http::StatusCode;
 use std::env;
 use std::io;
+use std::sync::Arc;
 use thiserror::Error;
 
 /// Type of general docker error response
@@ -8,16 +10,63 @@ use thiserror::Error;
 #[error("{message}")]
 pub struct DockerError {
     pub message: String,
+    /// status code of the response
+    #[serde(skip)]
+    pub status: Option<StatusCode>,
 }
 
+/// Category of a docker error response
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DockerErrorKind {
+    /// No such object (container, image, network, ...)
+    NotFound,
+    /// Conflict with the current state of the object (e.g. the name is already in use)
+    Conflict,
+    /// The object is already in the requested state
+    NotModified,
+    /// The port to be bound is already allocated
+    PortInUse,
+    /// Any other errors
+    Other,
+}
+
+impl DockerError {
+    /// Classify this error from the status code and the message
+    pub fn kind(&self) -> DockerErrorKind {
+        let message = self.message.to_lowercase();
+        if message.contains("port is already allocated")
+            || message.contains("address already in use")
+        {
+            return DockerErrorKind::PortInUse;
+        }
+        match self.status {
+            Some(StatusCode::NOT_FOUND) => DockerErrorKind::NotFound,
+            Some(StatusCode::CONFLICT) => DockerErrorKind::Conflict,
+            Some(StatusCode::NOT_MODIFIED) => DockerErrorKind::NotModified,
+            _ if message.contains("no such") || message.contains("not found") => {
+                DockerErrorKind::NotFound
+            }
+            _ if message.contains("conflict") || message.contains("is already in use") => {
+                DockerErrorKind::Conflict
+            }
+            _ => DockerErrorKind::Other,
+        }
+    }
+}
+
+/// Cause of a body error when the deadline of a streaming response elapsed
+#[derive(Debug, Error)]
+#[error("deadline elapsed")]
+pub(crate) struct DeadlineElapsed;
+
 #[derive(Error, Debug)]
 pub enum Error {
     #[error("io error")]
-    Io(#[from] io::Error),
+    Io(#[source] io::Error),
     #[error("envvar error")]
     Envvar(#[from] env::VarError),
     #[error("hyper error")]
-    Hyper(#[from] hyper::Error),
+    Hyper(#[source] hyper::Error),
     #[error("json error")]
     Json(#[from] serde_json::Error),
     #[error("docker error")]
@@ -31,6 +80,14 @@ pub enum Error {
         var: String,
         source: http::uri::InvalidUri,
     },
+    #[error("invalid url: {}", url)]
+    InvalidUrl {
+        url: String,
+        source: url::ParseError,
+    },
+    /// The url has no host to authenticate to, e.g. `file:` or `mailto:` urls
+    #[error("url can not have credentials: {}", url)]
+    UrlCannotHaveCredentials { url: String },
     #[cfg(feature = "native-tls")]
     #[error("ssl error")]
     NativeTls(#[from] native_tls::Error),
@@ -57,4 +114,100 @@ pub enum Error {
     Poison { message: String },
     #[error("unknown error: {}", message)]
     Unknown { message: String },
+    /// `Docker::build_image_remote` was called without `ContainerBuildOptions::remote`
+    #[error("remote of the build is not set")]
+    MissingRemote,
+    /// `keys` can not be parsed as `DetachKeys`
+    #[error("invalid detach keys {:?}: {}", keys, reason)]
+    InvalidDetachKeys { keys: String, reason: String },
+    /// An operation's deadline or timeout elapsed
+    ///
+    /// Returned when
+    /// * the deadline set by `Docker::with_deadline` elapsed,
+    /// * the container was not removed within the timeout of `Docker::wait_container_removed`, or
+    /// * the daemon was not ready within the timeout of `Docker::wait_until_ready`.
+    #[error("deadline elapsed")]
+    Timeout,
+    /// Error of the stream shared by `AttachBroadcast`
+    #[error("broadcast error")]
+    Broadcast(#[source] Arc<Error>),
+    /// A subscriber of `AttachBroadcast` fell behind and `skipped` frames were dropped
+    #[error("lagged behind: {} frames skipped", skipped)]
+    Lagged { skipped: u64 },
+}
+
+/// Whether `err` is caused by an elapsed deadline of a streaming response
+fn is_deadline_elapsed(mut err: &(dyn std::error::Error + 'static)) -> bool {
+    loop {
+        if err.is::<DeadlineElapsed>() {
+            return true;
+        }
+        match err.source() {
+            Some(source) => err = source,
+            None => return false,
+        }
+    }
+}
+
+impl From<hyper::Error> for Error {
+    fn from(err: hyper::Error) -> Self {
+        if is_deadline_elapsed(&err) {
+            Error::Timeout
+        } else {
+            Error::Hyper(err)
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        // streaming responses are often read through `io::Error`
+        let elapsed = err
+            .get_ref()
+            .map_or(false, |inner| is_deadline_elapsed(inner));
+        if elapsed {
+            Error::Timeout
+        } else {
+            Error::Io(err)
+        }
+    }
+}
+
+/// Result type of fallible operations of this crate
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(status: Option<StatusCode>, message: &str) -> DockerError {
+        DockerError {
+            message: message.to_owned(),
+            status,
+        }
+    }
+
+    #[test]
+    fn docker_error_kind() {
+        use DockerErrorKind::*;
+        let cases = [
+            (Some(StatusCode::NOT_FOUND), "No such container: foo", NotFound),
+            (
+                Some(StatusCode::CONFLICT),
+                r#"Conflict. The container name "/foo" is already in use"#,
+                Conflict,
+            ),
+            (Some(StatusCode::NOT_MODIFIED), "", NotModified),
+            (
+                Some(StatusCode::INTERNAL_SERVER_ERROR),
+                "driver failed programming external connectivity: Bind for 0.0.0.0:80 failed: port is already allocated",
+                PortInUse,
+            ),
+            (None, "No such image: alpine:3.9", NotFound),
+            (Some(StatusCode::INTERNAL_SERVER_ERROR), "oops", Other),
+        ];
+        for (status, message, kind) in cases {
+            assert_eq!(error(status, message).kind(), kind, "{message}");
+        }
+    }
 }
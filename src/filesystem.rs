@@ -19,3 +19,166 @@ pub struct XDockerContainerPathStat {
     pub mtime: String,
     pub linkTarget: String,
 }
+
+// bits of `os.FileMode` of go
+const MODE_DIR: u64 = 1 << 31;
+const MODE_SYMLINK: u64 = 1 << 27;
+const MODE_PERM: u64 = 0o777;
+
+impl XDockerContainerPathStat {
+    /// Whether the path is a directory
+    pub fn is_dir(&self) -> bool {
+        self.mode & MODE_DIR != 0
+    }
+
+    /// Whether the path is a symbolic link
+    pub fn is_symlink(&self) -> bool {
+        self.mode & MODE_SYMLINK != 0
+    }
+
+    /// Unix permission bits (e.g. `0o755`)
+    pub fn permissions(&self) -> u32 {
+        (self.mode & MODE_PERM) as u32
+    }
+}
+
+/// Compression of an archive, detected from its magic bytes
+#[cfg(feature = "compression")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Compression {
+    None,
+    Gzip,
+    Bzip2,
+    Xz,
+}
+
+#[cfg(feature = "compression")]
+impl Compression {
+    /// Length of the longest magic bytes
+    const MAGIC_LEN: usize = 6;
+
+    fn detect(head: &[u8]) -> Self {
+        if head.starts_with(&[0x1f, 0x8b]) {
+            Compression::Gzip
+        } else if head.starts_with(b"BZh") {
+            Compression::Bzip2
+        } else if head.starts_with(&[0xfd, b'7', b'z', b'X', b'Z', 0x00]) {
+            Compression::Xz
+        } else {
+            Compression::None
+        }
+    }
+}
+
+/// Decompress a gzip, bzip2 or xz compressed tar archive
+///
+/// # Summary
+/// The compression is detected from the magic bytes at the head of `stream`,
+/// like the daemon does for archives uploaded by `put_file`.
+/// An uncompressed archive is passed through as it is.
+#[cfg(feature = "compression")]
+pub fn decompress_tar_stream<S>(
+    stream: S,
+) -> futures::stream::BoxStream<'static, Result<bytes::Bytes, crate::errors::Error>>
+where
+    S: futures::stream::Stream<Item = Result<bytes::Bytes, crate::errors::Error>> + Send + 'static,
+{
+    use crate::errors::Error as DwError;
+    use async_compression::tokio::bufread::{BzDecoder, GzipDecoder, XzDecoder};
+    use futures::stream::{StreamExt, TryStreamExt};
+    use tokio::io::AsyncRead;
+
+    let decompressed = async move {
+        let mut stream = stream.boxed();
+        let mut head = Vec::new();
+        while head.len() < Compression::MAGIC_LEN {
+            match stream.try_next().await? {
+                Some(chunk) => head.extend_from_slice(&chunk),
+                None => break,
+            }
+        }
+        let compression = Compression::detect(&head);
+        let archive = futures::stream::once(futures::future::ok(bytes::Bytes::from(head)))
+            .chain(stream)
+            .map_err(|err| match err {
+                DwError::Io(err) => err,
+                err => std::io::Error::new(std::io::ErrorKind::Other, err),
+            });
+        let archive = tokio_util::io::StreamReader::new(archive);
+        let reader: Box<dyn AsyncRead + Send + Unpin> = match compression {
+            Compression::None => Box::new(archive),
+            Compression::Gzip => {
+                let mut decoder = GzipDecoder::new(archive);
+                decoder.multiple_members(true);
+                Box::new(decoder)
+            }
+            Compression::Bzip2 => Box::new(BzDecoder::new(archive)),
+            Compression::Xz => Box::new(XzDecoder::new(archive)),
+        };
+        Ok::<_, DwError>(tokio_util::io::ReaderStream::new(reader).map_err(DwError::from))
+    };
+    futures::stream::once(decompressed).try_flatten().boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn path_stat_mode() {
+        let stat: XDockerContainerPathStat = serde_json::from_str(
+            r#"{"name":"bin","size":4096,"mode":2147484141,"mtime":"2021-01-01T00:00:00Z","linkTarget":""}"#,
+        )
+        .unwrap();
+        assert!(stat.is_dir());
+        assert!(!stat.is_symlink());
+        assert_eq!(stat.permissions(), 0o755);
+
+        let stat: XDockerContainerPathStat = serde_json::from_str(
+            r#"{"name":"sh","size":4,"mode":134218239,"mtime":"2021-01-01T00:00:00Z","linkTarget":"/bin/dash"}"#,
+        )
+        .unwrap();
+        assert!(!stat.is_dir());
+        assert!(stat.is_symlink());
+        assert_eq!(stat.permissions(), 0o777);
+    }
+
+    #[cfg(feature = "compression")]
+    #[tokio::test]
+    async fn decompress_tar_stream_formats() {
+        use async_compression::tokio::bufread::{BzEncoder, GzipEncoder, XzEncoder};
+        use futures::stream::TryStreamExt;
+        use tokio::io::AsyncReadExt;
+
+        let archive: Vec<u8> = (0..10240u32).map(|i| (i % 251) as u8).collect();
+        let mut gzip = Vec::new();
+        GzipEncoder::new(archive.as_slice())
+            .read_to_end(&mut gzip)
+            .await
+            .unwrap();
+        let mut bzip2 = Vec::new();
+        BzEncoder::new(archive.as_slice())
+            .read_to_end(&mut bzip2)
+            .await
+            .unwrap();
+        let mut xz = Vec::new();
+        XzEncoder::new(archive.as_slice())
+            .read_to_end(&mut xz)
+            .await
+            .unwrap();
+
+        for compressed in [archive.clone(), gzip, bzip2, xz] {
+            // split into small chunks to check the detection over chunks
+            let chunks: Vec<Result<bytes::Bytes, crate::errors::Error>> = compressed
+                .chunks(3)
+                .map(|chunk| Ok(bytes::Bytes::copy_from_slice(chunk)))
+                .collect();
+            let decompressed: Vec<u8> = decompress_tar_stream(futures::stream::iter(chunks))
+                .map_ok(|chunk| chunk.to_vec())
+                .try_concat()
+                .await
+                .unwrap();
+            assert_eq!(decompressed, archive);
+        }
+    }
+}
@@ -14,7 +14,7 @@ pub trait HttpClient {
         path: &str,
     ) -> Result<Response<hyper::Body>, Self::Err>;
 
-    async fn head(&self, headers: &HeaderMap, path: &str) -> Result<HeaderMap, Self::Err>;
+    async fn head(&self, headers: &HeaderMap, path: &str) -> Result<Response<()>, Self::Err>;
 
     async fn post(
         &self,
@@ -53,6 +53,26 @@ pub trait HttpClient {
         path: &str,
         file: &Path,
     ) -> Result<Response<Vec<u8>>, Self::Err>;
+
+    /// post a streaming body
+    ///
+    /// The body can not be resent, so redirections are not followed.
+    async fn post_body(
+        &self,
+        headers: &HeaderMap,
+        path: &str,
+        body: hyper::Body,
+    ) -> Result<Response<Vec<u8>>, Self::Err>;
+
+    /// put a streaming body
+    ///
+    /// The body can not be resent, so redirections are not followed.
+    async fn put_body(
+        &self,
+        headers: &HeaderMap,
+        path: &str,
+        body: hyper::Body,
+    ) -> Result<Response<Vec<u8>>, Self::Err>;
 }
 
 /// Access to inner HttpClient
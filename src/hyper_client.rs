@@ -1,32 +1,77 @@
-use crate::errors::Error as DwError;
+use crate::errors::{DeadlineElapsed, Error as DwError};
 use crate::http_client::HttpClient;
+use futures::stream::{Stream, StreamExt};
 use http::{HeaderMap, Request, Response};
 use hyper::Uri;
+use std::future::Future;
 use std::path::Path;
 use std::str::FromStr;
+use tokio::time::Instant;
 
+/// Each client is kept with its connector, to build clients not sharing its connection pool
 #[allow(clippy::enum_variant_names)]
 #[derive(Clone, Debug)]
 enum Client {
-    HttpClient(hyper::Client<hyper::client::HttpConnector>),
+    HttpClient(
+        hyper::Client<hyper::client::HttpConnector>,
+        hyper::client::HttpConnector,
+    ),
     #[cfg(feature = "openssl")]
-    HttpsClient(hyper::Client<hyper_tls::HttpsConnector<hyper::client::HttpConnector>>),
+    HttpsClient(
+        hyper::Client<hyper_tls::HttpsConnector<hyper::client::HttpConnector>>,
+        hyper_tls::HttpsConnector<hyper::client::HttpConnector>,
+    ),
     #[cfg(feature = "rustls")]
-    HttpsClient(hyper::Client<hyper_rustls::HttpsConnector<hyper::client::HttpConnector>>),
+    HttpsClient(
+        hyper::Client<hyper_rustls::HttpsConnector<hyper::client::HttpConnector>>,
+        hyper_rustls::HttpsConnector<hyper::client::HttpConnector>,
+    ),
     #[cfg(unix)]
-    UnixClient(hyper::Client<hyperlocal::UnixConnector>),
+    UnixClient(
+        hyper::Client<hyperlocal::UnixConnector>,
+        hyperlocal::UnixConnector,
+    ),
 }
 
 impl Client {
     fn request(&self, req: Request<hyper::Body>) -> hyper::client::ResponseFuture {
         match self {
-            Client::HttpClient(http_client) => http_client.request(req),
+            Client::HttpClient(http_client, _) => http_client.request(req),
             #[cfg(feature = "openssl")]
-            Client::HttpsClient(https_client) => https_client.request(req),
+            Client::HttpsClient(https_client, _) => https_client.request(req),
             #[cfg(feature = "rustls")]
-            Client::HttpsClient(https_client) => https_client.request(req),
+            Client::HttpsClient(https_client, _) => https_client.request(req),
             #[cfg(unix)]
-            Client::UnixClient(unix_client) => unix_client.request(req),
+            Client::UnixClient(unix_client, _) => unix_client.request(req),
+        }
+    }
+
+    /// Client with the same connector and a connection pool of its own, which keeps no idle
+    /// connections
+    fn unpooled(&self) -> Self {
+        fn builder() -> hyper::client::Builder {
+            let mut builder = hyper::Client::builder();
+            builder
+                .pool_idle_timeout(std::time::Duration::from_millis(0))
+                .pool_max_idle_per_host(0);
+            builder
+        }
+        match self {
+            Client::HttpClient(_, connector) => {
+                Client::HttpClient(builder().build(connector.clone()), connector.clone())
+            }
+            #[cfg(feature = "openssl")]
+            Client::HttpsClient(_, connector) => {
+                Client::HttpsClient(builder().build(connector.clone()), connector.clone())
+            }
+            #[cfg(feature = "rustls")]
+            Client::HttpsClient(_, connector) => {
+                Client::HttpsClient(builder().build(connector.clone()), connector.clone())
+            }
+            #[cfg(unix)]
+            Client::UnixClient(_, connector) => {
+                Client::UnixClient(builder().build(*connector), *connector)
+            }
         }
     }
 }
@@ -38,6 +83,8 @@ pub struct HyperClient {
     client: Client,
     /// base connection address
     base: Uri,
+    /// deadline of every request, including reading streaming responses
+    deadline: Option<Instant>,
 }
 
 fn join_uri(uri: &Uri, path: &str) -> Result<Uri, DwError> {
@@ -120,15 +167,110 @@ async fn request_with_redirect<T: Into<hyper::Body> + Sync + Send + 'static + Cl
     }
 }
 
+async fn request_once(
+    client: &Client,
+    method: http::Method,
+    uri: Uri,
+    headers: &HeaderMap,
+    body: hyper::Body,
+) -> Result<http::Response<hyper::Body>, DwError> {
+    let request = request_builder(&method, &uri, headers).body(body)?;
+    Ok(client.request(request).await?)
+}
+
 async fn fetch_body(resp: http::Response<hyper::Body>) -> Result<http::Response<Vec<u8>>, DwError> {
     let (p, b) = resp.into_parts();
     let b = hyper::body::to_bytes(b).await?.to_vec();
     Ok(Response::from_parts(p, b))
 }
 
+/// Yield chunks of `body` until `deadline`, then fail with `DeadlineElapsed`
+fn until_deadline(
+    mut body: hyper::Body,
+    deadline: Instant,
+) -> impl Stream<Item = Result<bytes::Bytes, Box<dyn std::error::Error + Send + Sync>>> {
+    async_stream::stream! {
+        let sleep = tokio::time::sleep_until(deadline);
+        tokio::pin!(sleep);
+        loop {
+            tokio::select! {
+                chunk = body.next() => match chunk {
+                    Some(chunk) => yield chunk.map_err(Into::into),
+                    None => break,
+                },
+                () = &mut sleep => {
+                    yield Err(DeadlineElapsed.into());
+                    break;
+                }
+            }
+        }
+    }
+}
+
 impl HyperClient {
     fn new(client: Client, base: Uri) -> Self {
-        Self { client, base }
+        Self {
+            client,
+            base,
+            deadline: None,
+        }
+    }
+
+    /// Clone this client with a connection pool of its own
+    ///
+    /// Connections of a pool are driven by the runtime they are opened on, so a client used on
+    /// a short-lived runtime must not share the pool with clients used on other runtimes.
+    pub(crate) fn unpooled(&self) -> Self {
+        Self {
+            client: self.client.unpooled(),
+            ..self.clone()
+        }
+    }
+
+    /// Clone this client with a deadline of every request
+    pub(crate) fn with_deadline(&self, deadline: Instant) -> Self {
+        Self {
+            deadline: Some(deadline),
+            ..self.clone()
+        }
+    }
+
+    async fn bounded<T>(
+        &self,
+        future: impl Future<Output = Result<T, DwError>>,
+    ) -> Result<T, DwError> {
+        match self.deadline {
+            Some(deadline) => tokio::time::timeout_at(deadline, future)
+                .await
+                .map_err(|_| DwError::Timeout)?,
+            None => future.await,
+        }
+    }
+
+    fn bound_body(&self, res: Response<hyper::Body>) -> Response<hyper::Body> {
+        match self.deadline {
+            Some(deadline) => {
+                res.map(|body| hyper::Body::wrap_stream(until_deadline(body, deadline)))
+            }
+            None => res,
+        }
+    }
+
+    /// Send a request whose uri is a path (and query) on the daemon
+    #[cfg(feature = "tower")]
+    pub(crate) async fn send(
+        &self,
+        mut request: Request<hyper::Body>,
+    ) -> Result<Response<hyper::Body>, DwError> {
+        let path = request
+            .uri()
+            .path_and_query()
+            .map_or("/", |path| path.as_str())
+            .to_owned();
+        *request.uri_mut() = join_uri(&self.base, &path)?;
+        self.bounded(async { Ok(self.client.request(request).await?) })
+            .await
+            .map(|res| self.bound_body(res))
     }
 
     /// path to unix socket
@@ -141,7 +283,7 @@ impl HyperClient {
             .pool_idle_timeout(std::time::Duration::from_millis(0))
             .pool_max_idle_per_host(0)
             .build(hyperlocal::UnixConnector);
-        Self::new(Client::UnixClient(client), url)
+        Self::new(Client::UnixClient(client, hyperlocal::UnixConnector), url)
     }
 
     #[cfg(feature = "openssl")]
@@ -174,8 +316,8 @@ impl HyperClient {
         let mut http = hyper::client::HttpConnector::new();
         http.enforce_http(false);
         let https = hyper_tls::HttpsConnector::from((http, builder.build()?.into()));
-        let client = hyper::Client::builder().build::<_, hyper::Body>(https);
-        Ok(Self::new(Client::HttpsClient(client), url))
+        let client = hyper::Client::builder().build::<_, hyper::Body>(https.clone());
+        Ok(Self::new(Client::HttpsClient(client, https), url))
     }
 
     #[cfg(feature = "rustls")]
@@ -235,8 +377,8 @@ impl HyperClient {
             .https_or_http()
             .enable_all_versions()
             .build();
-        let client = hyper::Client::builder().build::<_, hyper::Body>(https);
-        Ok(Self::new(Client::HttpsClient(client), url))
+        let client = hyper::Client::builder().build::<_, hyper::Body>(https.clone());
+        Ok(Self::new(Client::HttpsClient(client, https), url))
     }
 
     pub fn connect_with_http(addr: &str) -> Result<Self, DwError> {
@@ -246,7 +388,9 @@ impl HyperClient {
             var: addr_https,
             source: err,
         })?;
-        Ok(Self::new(Client::HttpClient(hyper::Client::new()), url))
+        let http = hyper::client::HttpConnector::new();
+        let client = hyper::Client::builder().build::<_, hyper::Body>(http.clone());
+        Ok(Self::new(Client::HttpClient(client, http), url))
     }
 }
 
@@ -255,50 +399,60 @@ impl HttpClient for HyperClient {
     type Err = DwError;
 
     async fn get(&self, headers: &HeaderMap, path: &str) -> Result<Response<Vec<u8>>, Self::Err> {
-        let url = join_uri(&self.base, path)?;
-
-        let res = request_with_redirect::<Vec<u8>>(
-            self.client.clone(),
-            http::Method::GET,
-            url,
-            headers.clone(),
-            None,
-        )
-        .await?;
-        let res = fetch_body(res).await?;
-        Ok(res)
+        self.bounded(async {
+            let url = join_uri(&self.base, path)?;
+
+            let res = request_with_redirect::<Vec<u8>>(
+                self.client.clone(),
+                http::Method::GET,
+                url,
+                headers.clone(),
+                None,
+            )
+            .await?;
+            let res = fetch_body(res).await?;
+            Ok(res)
+        })
+        .await
     }
     async fn get_stream(
         &self,
         headers: &HeaderMap,
         path: &str,
     ) -> Result<Response<hyper::Body>, Self::Err> {
-        let url = join_uri(&self.base, path)?;
-
-        let res = request_with_redirect::<Vec<u8>>(
-            self.client.clone(),
-            http::Method::GET,
-            url,
-            headers.clone(),
-            None,
-        )
-        .await?;
-        Ok(res)
+        self.bounded(async {
+            let url = join_uri(&self.base, path)?;
+
+            let res = request_with_redirect::<Vec<u8>>(
+                self.client.clone(),
+                http::Method::GET,
+                url,
+                headers.clone(),
+                None,
+            )
+            .await?;
+            Ok(res)
+        })
+        .await
+        .map(|res| self.bound_body(res))
     }
 
-    async fn head(&self, headers: &HeaderMap, path: &str) -> Result<HeaderMap, Self::Err> {
-        let url = join_uri(&self.base, path)?;
-
-        let res = request_with_redirect::<Vec<u8>>(
-            self.client.clone(),
-            http::Method::HEAD,
-            url,
-            headers.clone(),
-            None,
-        )
-        .await?;
-
-        Ok(res.headers().clone())
+    async fn head(&self, headers: &HeaderMap, path: &str) -> Result<Response<()>, Self::Err> {
+        self.bounded(async {
+            let url = join_uri(&self.base, path)?;
+
+            let res = request_with_redirect::<Vec<u8>>(
+                self.client.clone(),
+                http::Method::HEAD,
+                url,
+                headers.clone(),
+                None,
+            )
+            .await?;
+
+            Ok(res.map(|_| ()))
+        })
+        .await
     }
 
     async fn post(
@@ -307,18 +461,21 @@ impl HttpClient for HyperClient {
         path: &str,
         body: &str,
     ) -> Result<Response<Vec<u8>>, Self::Err> {
-        let url = join_uri(&self.base, path)?;
-
-        let res = request_with_redirect(
-            self.client.clone(),
-            http::Method::POST,
-            url,
-            headers.clone(),
-            Some(body.to_string()),
-        )
-        .await?;
-        let res = fetch_body(res).await?;
-        Ok(res)
+        self.bounded(async {
+            let url = join_uri(&self.base, path)?;
+
+            let res = request_with_redirect(
+                self.client.clone(),
+                http::Method::POST,
+                url,
+                headers.clone(),
+                Some(body.to_string()),
+            )
+            .await?;
+            let res = fetch_body(res).await?;
+            Ok(res)
+        })
+        .await
     }
 
     async fn post_stream(
@@ -327,17 +484,21 @@ impl HttpClient for HyperClient {
         path: &str,
         body: &str,
     ) -> Result<Response<hyper::Body>, Self::Err> {
-        let url = join_uri(&self.base, path)?;
-
-        let res = request_with_redirect(
-            self.client.clone(),
-            http::Method::POST,
-            url,
-            headers.clone(),
-            Some(body.to_string()),
-        )
-        .await?;
-        Ok(res)
+        self.bounded(async {
+            let url = join_uri(&self.base, path)?;
+
+            let res = request_with_redirect(
+                self.client.clone(),
+                http::Method::POST,
+                url,
+                headers.clone(),
+                Some(body.to_string()),
+            )
+            .await?;
+            Ok(res)
+        })
+        .await
+        .map(|res| self.bound_body(res))
     }
 
     async fn post_file(
@@ -346,23 +507,26 @@ impl HttpClient for HyperClient {
         path: &str,
         file: &Path,
     ) -> Result<Response<Vec<u8>>, Self::Err> {
-        let mut content = tokio::fs::File::open(file).await?;
-        let url = join_uri(&self.base, path)?;
-
-        use tokio::io::AsyncReadExt;
-        let mut buf = Vec::new();
-        content.read_to_end(&mut buf).await?;
-
-        let res = request_with_redirect(
-            self.client.clone(),
-            http::Method::POST,
-            url,
-            headers.clone(),
-            Some(buf),
-        )
-        .await?;
-        let res = fetch_body(res).await?;
-        Ok(res)
+        self.bounded(async {
+            let mut content = tokio::fs::File::open(file).await?;
+            let url = join_uri(&self.base, path)?;
+
+            use tokio::io::AsyncReadExt;
+            let mut buf = Vec::new();
+            content.read_to_end(&mut buf).await?;
+
+            let res = request_with_redirect(
+                self.client.clone(),
+                http::Method::POST,
+                url,
+                headers.clone(),
+                Some(buf),
+            )
+            .await?;
+            let res = fetch_body(res).await?;
+            Ok(res)
+        })
+        .await
     }
 
     async fn post_file_stream(
@@ -371,22 +535,26 @@ impl HttpClient for HyperClient {
         path: &str,
         file: &Path,
     ) -> Result<Response<hyper::Body>, Self::Err> {
-        let mut content = tokio::fs::File::open(file).await?;
-        let url = join_uri(&self.base, path)?;
-
-        use tokio::io::AsyncReadExt;
-        let mut buf = Vec::new();
-        content.read_to_end(&mut buf).await?;
-
-        let res = request_with_redirect(
-            self.client.clone(),
-            http::Method::POST,
-            url,
-            headers.clone(),
-            Some(buf),
-        )
-        .await?;
-        Ok(res)
+        self.bounded(async {
+            let mut content = tokio::fs::File::open(file).await?;
+            let url = join_uri(&self.base, path)?;
+
+            use tokio::io::AsyncReadExt;
+            let mut buf = Vec::new();
+            content.read_to_end(&mut buf).await?;
+
+            let res = request_with_redirect(
+                self.client.clone(),
+                http::Method::POST,
+                url,
+                headers.clone(),
+                Some(buf),
+            )
+            .await?;
+            Ok(res)
+        })
+        .await
+        .map(|res| self.bound_body(res))
     }
 
     async fn delete(
@@ -394,18 +562,21 @@ impl HttpClient for HyperClient {
         headers: &HeaderMap,
         path: &str,
     ) -> Result<Response<Vec<u8>>, Self::Err> {
-        let url = join_uri(&self.base, path)?;
-
-        let res = request_with_redirect::<Vec<u8>>(
-            self.client.clone(),
-            http::Method::DELETE,
-            url,
-            headers.clone(),
-            None,
-        )
-        .await?;
-        let res = fetch_body(res).await?;
-        Ok(res)
+        self.bounded(async {
+            let url = join_uri(&self.base, path)?;
+
+            let res = request_with_redirect::<Vec<u8>>(
+                self.client.clone(),
+                http::Method::DELETE,
+                url,
+                headers.clone(),
+                None,
+            )
+            .await?;
+            let res = fetch_body(res).await?;
+            Ok(res)
+        })
+        .await
     }
 
     async fn put_file(
@@ -414,22 +585,55 @@ impl HttpClient for HyperClient {
         path: &str,
         file: &Path,
     ) -> Result<Response<Vec<u8>>, Self::Err> {
-        let mut content = tokio::fs::File::open(file).await?;
-        let url = join_uri(&self.base, path)?;
-
-        use tokio::io::AsyncReadExt;
-        let mut buf = Vec::new();
-        content.read_to_end(&mut buf).await?;
-
-        let res = request_with_redirect(
-            self.client.clone(),
-            http::Method::PUT,
-            url,
-            headers.clone(),
-            Some(buf),
-        )
-        .await?;
-        let res = fetch_body(res).await?;
-        Ok(res)
+        self.bounded(async {
+            let mut content = tokio::fs::File::open(file).await?;
+            let url = join_uri(&self.base, path)?;
+
+            use tokio::io::AsyncReadExt;
+            let mut buf = Vec::new();
+            content.read_to_end(&mut buf).await?;
+
+            let res = request_with_redirect(
+                self.client.clone(),
+                http::Method::PUT,
+                url,
+                headers.clone(),
+                Some(buf),
+            )
+            .await?;
+            let res = fetch_body(res).await?;
+            Ok(res)
+        })
+        .await
+    }
+
+    async fn post_body(
+        &self,
+        headers: &HeaderMap,
+        path: &str,
+        body: hyper::Body,
+    ) -> Result<Response<Vec<u8>>, Self::Err> {
+        self.bounded(async {
+            let url = join_uri(&self.base, path)?;
+            let res = request_once(&self.client, http::Method::POST, url, headers, body).await?;
+            let res = fetch_body(res).await?;
+            Ok(res)
+        })
+        .await
+    }
+
+    async fn put_body(
+        &self,
+        headers: &HeaderMap,
+        path: &str,
+        body: hyper::Body,
+    ) -> Result<Response<Vec<u8>>, Self::Err> {
+        self.bounded(async {
+            let url = join_uri(&self.base, path)?;
+            let res = request_once(&self.client, http::Method::PUT, url, headers, body).await?;
+            let res = fetch_body(res).await?;
+            Ok(res)
+        })
+        .await
     }
 }
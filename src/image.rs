@@ -1,20 +1,13 @@
 use crate::container::Config;
+use crate::options::LabelSelector;
+use crate::serde_helpers::null_to_default;
 use chrono::offset::FixedOffset;
 use chrono::DateTime;
-use serde::de::{DeserializeOwned, Deserializer};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::{fmt, result};
 
-fn null_to_default<'de, D, T>(de: D) -> Result<T, D::Error>
-where
-    D: Deserializer<'de>,
-    T: DeserializeOwned + Default,
-{
-    let actual: Option<T> = Option::deserialize(de)?;
-    Ok(actual.unwrap_or_default())
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[allow(non_snake_case)]
 pub struct FoundImage {
@@ -25,6 +18,7 @@ pub struct FoundImage {
     pub star_count: u32,
 }
 
+/// Filters of `Docker::search_images`
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[allow(non_snake_case)]
 pub struct ImageFilters {
@@ -37,6 +31,7 @@ pub struct ImageFilters {
     #[serde(with = "format::boolopt_as_strlist")]
     pub is_official: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(with = "format::u64opt_as_strlist")]
     pub stars: Option<u64>,
 }
 
@@ -61,6 +56,41 @@ impl ImageFilters {
     }
 }
 
+/// Filters of `Docker::list_images`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Default)]
+pub struct ListImageFilters {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(with = "format::boolopt_as_strlist")]
+    dangling: Option<bool>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    label: Vec<LabelSelector>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    reference: Vec<String>,
+}
+
+impl ListImageFilters {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn dangling(&mut self, dangling: bool) -> &mut Self {
+        self.dangling = Some(dangling);
+        self
+    }
+
+    /// filter by a label key (`key`) or a key and its value (`key=value`)
+    pub fn label<L: Into<LabelSelector>>(&mut self, label: L) -> &mut Self {
+        self.label.push(label.into());
+        self
+    }
+
+    /// filter by an image reference pattern, e.g. `alpine` or `alpine:3.*`
+    pub fn reference(&mut self, reference: &str) -> &mut Self {
+        self.reference.push(reference.to_owned());
+        self
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[allow(non_snake_case)]
 pub struct SummaryImage {
@@ -120,6 +150,38 @@ pub struct GraphDriver {
     pub Data: HashMap<String, String>,
 }
 
+impl GraphDriver {
+    /// `LowerDir` of the overlay driver, from the top layer to the bottom
+    ///
+    /// Empty if the driver does not report it, e.g. for an image of a single layer.
+    pub fn lower_dirs(&self) -> Vec<PathBuf> {
+        self.Data
+            .get("LowerDir")
+            .map(|dirs| {
+                dirs.split(':')
+                    .filter(|dir| !dir.is_empty())
+                    .map(PathBuf::from)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// `MergedDir` of the overlay driver
+    pub fn merged_dir(&self) -> Option<PathBuf> {
+        self.Data.get("MergedDir").map(PathBuf::from)
+    }
+
+    /// `UpperDir` of the overlay driver
+    pub fn upper_dir(&self) -> Option<PathBuf> {
+        self.Data.get("UpperDir").map(PathBuf::from)
+    }
+
+    /// `WorkDir` of the overlay driver
+    pub fn work_dir(&self) -> Option<PathBuf> {
+        self.Data.get("WorkDir").map(PathBuf::from)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[allow(non_snake_case)]
 pub struct RootFS {
@@ -135,7 +197,7 @@ pub struct ImageStatus {
     pub error: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct ImageId {
     id: String,
 }
@@ -203,6 +265,43 @@ pub mod format {
         }
     }
 
+    pub mod u64opt_as_strlist {
+        use super::*;
+
+        pub fn serialize<S>(opt: &Option<u64>, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            if let Some(n) = opt {
+                serializer.serialize_some(&[&n.to_string()])
+            } else {
+                serializer.serialize_none()
+            }
+        }
+
+        pub fn deserialize<'de, D>(de: D) -> Result<Option<u64>, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            let sopt = Option::<Vec<String>>::deserialize(de)?;
+            if let Some(s) = sopt {
+                if s.len() == 1 {
+                    match s[0].parse::<u64>() {
+                        Ok(n) => Ok(Some(n)),
+                        Err(_) => Err(de::Error::invalid_value(
+                            de::Unexpected::Str(&s[0]),
+                            &r#"string represents u64"#,
+                        )),
+                    }
+                } else {
+                    Err(de::Error::invalid_length(s.len(), &"u64 string singleton"))
+                }
+            } else {
+                Ok(None)
+            }
+        }
+    }
+
     pub mod datetime_rfc3339 {
         use super::*;
         use chrono::offset::FixedOffset;
@@ -1,5 +1,6 @@
 //! Docker Engine API client
 
+pub mod build_context;
 pub mod checkpoint;
 pub mod container;
 pub mod credentials;
@@ -14,11 +15,15 @@ pub mod network;
 mod options;
 pub mod process;
 pub mod response;
+mod serde_helpers;
+#[cfg(feature = "tower")]
+mod service;
 pub mod signal;
 pub mod stats;
 pub mod system;
 mod test;
 pub mod version;
+pub mod volume;
 
-pub use docker::Docker;
+pub use docker::{AttachBroadcast, AttachStdin, ContainerGuard, Docker};
 pub use options::*;
@@ -1,4 +1,6 @@
 #![allow(clippy::new_without_default)]
+use crate::options::LabelSelector;
+use crate::serde_helpers::null_to_default;
 use log::warn;
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
@@ -29,7 +31,7 @@ pub struct Network {
 pub struct IPAM {
     pub Driver: String,
     pub Config: Option<Vec<IPAMConfig>>,
-    #[serde(deserialize_with = "format::null_to_default")]
+    #[serde(deserialize_with = "null_to_default")]
     pub Options: HashMap<String, String>,
 }
 
@@ -54,7 +56,7 @@ pub struct IPAMConfig {
     pub Gateway: Option<String>,
     #[serde(
         skip_serializing_if = "HashMap::is_empty",
-        deserialize_with = "format::null_to_default",
+        deserialize_with = "null_to_default",
         default
     )]
     /// This field is given by "macvlan" network.
@@ -87,7 +89,7 @@ pub struct ListNetworkFilters {
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub id: Vec<String>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
-    pub label: Vec<String>,
+    pub label: Vec<LabelSelector>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub name: Vec<String>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
@@ -116,8 +118,9 @@ impl ListNetworkFilters {
         self
     }
 
+    /// filter by a label key (`key`) or a key and its value (`key=value`)
     pub fn label(&mut self, label: Cow<str>) -> &mut Self {
-        self.label.push(label.into_owned());
+        self.label.push(label.as_ref().into());
         self
     }
 
@@ -174,7 +177,7 @@ impl PruneNetworkFilters {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
 pub struct LabelFilter(HashMap<String, Option<String>>);
 
 impl LabelFilter {
@@ -211,6 +214,9 @@ pub enum NetworkScope {
     Swarm,
     Global,
     Local,
+    /// unknown value reported by a newer daemon
+    #[serde(other)]
+    Unknown,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
@@ -218,6 +224,9 @@ pub enum NetworkScope {
 pub enum NetworkType {
     Custom,
     Builtin,
+    /// unknown value reported by a newer daemon
+    #[serde(other)]
+    Unknown,
 }
 
 /// request body of /networks/create api
@@ -346,7 +355,7 @@ pub struct CreateNetworkResponse {
 pub struct PruneNetworkResponse {
     #[serde(
         serialize_with = "format::vec_to_null",
-        deserialize_with = "format::null_to_default"
+        deserialize_with = "null_to_default"
     )]
     pub networks_deleted: Vec<String>,
 }
@@ -369,7 +378,7 @@ pub struct EndpointConfig {
     pub MacAddress: String,
     #[serde(
         serialize_with = "format::hashmap_to_null",
-        deserialize_with = "format::null_to_default",
+        deserialize_with = "null_to_default",
         default
     )]
     pub DriverOpts: HashMap<String, String>,
@@ -393,6 +402,22 @@ pub struct NetworkConnectOptions {
     pub EndpointConfig: EndpointConfig,
 }
 
+impl NetworkConnectOptions {
+    /// Connect `container` with the static IPv4 address `ipv4`
+    pub fn with_ipv4(container: &str, ipv4: Ipv4Addr) -> Self {
+        Self {
+            Container: container.to_owned(),
+            EndpointConfig: EndpointConfig {
+                IPAMConfig: Some(EndpointIPAMConfig {
+                    IPv4Address: ipv4.to_string(),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 #[allow(non_snake_case)]
 pub struct NetworkDisconnectOptions {
@@ -405,17 +430,7 @@ pub struct NetworkDisconnectOptions {
 mod format {
     use super::*;
 
-    use serde::de::{DeserializeOwned, Deserializer};
-    use serde::{ser::*, Deserialize, Serialize, Serializer};
-
-    pub fn null_to_default<'de, D, T>(de: D) -> Result<T, D::Error>
-    where
-        D: Deserializer<'de>,
-        T: DeserializeOwned + Default,
-    {
-        let actual: Option<T> = Option::deserialize(de)?;
-        Ok(actual.unwrap_or_default())
-    }
+    use serde::{ser::*, Serialize, Serializer};
 
     pub fn vec_to_null<T, S>(t: &Vec<T>, se: S) -> Result<S::Ok, S::Error>
     where
@@ -514,8 +529,8 @@ mod format {
             let mut map = serializer.serialize_map(None)?;
             for (k, v) in &self.0 {
                 let key = match v {
-                    Some(v) => format!("{k}={v}"),
-                    None => k.to_string(),
+                    Some(v) => LabelSelector::key_value(k, v),
+                    None => LabelSelector::key(k),
                 };
                 map.serialize_entry(&key, &true)?;
             }
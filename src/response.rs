@@ -5,10 +5,17 @@ use serde_json::value as json;
 use std::error::Error as StdError;
 use std::fmt;
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Clone, Serialize, Deserialize)]
+/// Progress of a layer in bytes
+///
+/// Either field may be missing, e.g. `total` is unknown while waiting
+/// and some daemons send only `total` while extracting.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ProgressDetail {
-    pub current: u64,
-    pub total: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<u64>,
 }
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Clone, Serialize, Deserialize)]
@@ -82,6 +89,39 @@ pub enum Response {
     Unknown(json::Value),
 }
 
+/// Progress message of pulling an image
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct PullProgress {
+    /// hash of image layer, or tag for messages about the whole image
+    pub id: Option<String>,
+    /// message like "Downloading" or "Pull complete"
+    pub status: String,
+    /// progress detail
+    pub detail: Option<ProgressDetail>,
+}
+
+impl PullProgress {
+    /// Extract the pull progress from a response of /images/create
+    ///
+    /// Returns `None` for responses which are not progress messages.
+    pub fn from_response(response: Response) -> Option<Result<Self, Error>> {
+        match response {
+            Response::Progress(progress) => Some(Ok(PullProgress {
+                id: Some(progress.id),
+                status: progress.status,
+                detail: progress.progressDetail,
+            })),
+            Response::Status(status) => Some(Ok(PullProgress {
+                id: status.id,
+                status: status.status,
+                detail: None,
+            })),
+            Response::Error(err) => Some(Err(err)),
+            _ => None,
+        }
+    }
+}
+
 impl fmt::Display for Error {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         write!(fmt, "{}: {}", self.error, self.errorDetail.message)
@@ -111,63 +151,15 @@ impl Response {
 
 mod progress_detail_opt {
     use super::*;
-    use serde::de::{self, Deserializer, MapAccess, Visitor};
+    use serde::de::Deserializer;
 
+    /// `{}` is deserialized into `None`
     pub fn deserialize<'de, D>(de: D) -> Result<Option<ProgressDetail>, D::Error>
     where
         D: Deserializer<'de>,
     {
-        struct OptVisitor;
-
-        impl<'de> Visitor<'de> for OptVisitor {
-            type Value = Option<ProgressDetail>;
-
-            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-                formatter.write_str("Option<ProgressDetail>")
-            }
-
-            fn visit_map<V>(self, mut map: V) -> Result<Self::Value, V::Error>
-            where
-                V: MapAccess<'de>,
-            {
-                let mut current = None;
-                let mut total = None;
-
-                match map.next_key()? {
-                    Some(mut key) => loop {
-                        match key {
-                            "current" => {
-                                if current.is_some() {
-                                    return Err(de::Error::duplicate_field("current"));
-                                }
-                                current = Some(map.next_value()?);
-                            }
-                            "total" => {
-                                if total.is_some() {
-                                    return Err(de::Error::duplicate_field("total"));
-                                }
-                                total = Some(map.next_value()?);
-                            }
-                            _ => return Err(de::Error::unknown_field(key, FIELDS)),
-                        };
-                        if let Some(key_) = map.next_key()? {
-                            key = key_;
-                        } else {
-                            break;
-                        }
-                    },
-                    None => return Ok(None), // {}
-                }
-
-                let current = current.ok_or_else(|| de::Error::missing_field("current"))?;
-                let total = total.ok_or_else(|| de::Error::missing_field("total"))?;
-
-                Ok(Some(ProgressDetail { current, total }))
-            }
-        }
-
-        const FIELDS: &[&str] = &["current", "total"];
-        de.deserialize_map(OptVisitor)
+        let detail = Option::<ProgressDetail>::deserialize(de)?;
+        Ok(detail.filter(|detail| detail.current.is_some() || detail.total.is_some()))
     }
 }
 
@@ -197,8 +189,8 @@ mod tests {
                         .into(),
                 status: "Downloading".to_owned(),
                 progressDetail: Some(ProgressDetail {
-                    current: 1596117,
-                    total: 86451485,
+                    current: Some(1596117),
+                    total: Some(86451485),
                 }),
             }),
             serde_json::from_str(s).unwrap()
@@ -231,6 +223,32 @@ mod tests {
         )
     }
 
+    #[test]
+    fn pull_progress() {
+        let s = r#"{"status":"Downloading","progressDetail":{"current":1,"total":2},"id":"18b8eb7e7f01"}"#;
+        assert_eq!(
+            PullProgress::from_response(serde_json::from_str(s).unwrap()),
+            Some(Ok(PullProgress {
+                id: Some("18b8eb7e7f01".to_owned()),
+                status: "Downloading".to_owned(),
+                detail: Some(ProgressDetail {
+                    current: Some(1),
+                    total: Some(2)
+                }),
+            }))
+        );
+        let s = r#"{"stream":"Step 1/2"}"#;
+        assert_eq!(
+            PullProgress::from_response(serde_json::from_str(s).unwrap()),
+            None
+        );
+        let s = r#"{"error":"oops","errorDetail":{"message":"oops"}}"#;
+        assert!(matches!(
+            PullProgress::from_response(serde_json::from_str(s).unwrap()),
+            Some(Err(_))
+        ));
+    }
+
     #[test]
     #[rustfmt::skip]
     fn error() {
@@ -0,0 +1,14 @@
+//! Helpers for serde attributes shared by the api types
+
+use serde::de::{DeserializeOwned, Deserializer};
+use serde::Deserialize;
+
+/// Deserialize `null` as the default value of `T`
+pub(crate) fn null_to_default<'de, D, T>(de: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned + Default,
+{
+    let actual: Option<T> = Option::deserialize(de)?;
+    Ok(actual.unwrap_or_default())
+}
@@ -0,0 +1,48 @@
+//! `tower::Service` implementation of `Docker`
+//!
+//! Enabled by the `tower` feature.
+//! This allows to route requests to the daemon through tower middlewares.
+
+use crate::docker::Docker;
+use crate::errors::Error as DwError;
+use futures::future::BoxFuture;
+use http::{Request, Response};
+use std::task::{Context, Poll};
+
+/// Send a raw request to the daemon
+///
+/// The uri of the request is a path and query on the daemon like `/containers/json?all=true`,
+/// which is resolved against the address `Docker` is connected to.
+/// Headers of `Docker` (e.g. the registry credential) are added unless the request has them.
+impl tower_service::Service<Request<hyper::Body>> for Docker {
+    type Response = Response<hyper::Body>;
+    type Error = DwError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        // hyper clients are always ready
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: Request<hyper::Body>) -> Self::Future {
+        let docker = self.clone();
+        Box::pin(async move { docker.send_request(request).await })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tower_service::Service;
+
+    fn assert_service<S: Service<Request<hyper::Body>> + Clone + Send + 'static>()
+    where
+        S::Future: Send,
+    {
+    }
+
+    #[test]
+    fn docker_is_service() {
+        assert_service::<Docker>();
+    }
+}
@@ -29,6 +29,30 @@ mod unix {
                 .map_err(|err| io::Error::from_raw_os_error(err as i32))?
                 .into())
         }
+
+        /// Parse a signal name like `SIGTERM`, `TERM` or `term`
+        pub fn from_name(name: &str) -> Result<Self, Error> {
+            let upper = name.to_ascii_uppercase();
+            let full = if upper.starts_with("SIG") {
+                upper
+            } else {
+                format!("SIG{upper}")
+            };
+            Ok(full
+                .parse::<NixSignal>()
+                .map_err(|_| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!("unknown signal: {}", name),
+                    )
+                })?
+                .into())
+        }
+
+        /// Name of the signal like `SIGTERM`
+        pub fn name(&self) -> &'static str {
+            self.0.as_str()
+        }
     }
 
     impl From<NixSignal> for Signal {
@@ -43,6 +67,22 @@ mod unix {
             self.0.next().map(Into::into)
         }
     }
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+        #[test]
+        fn name() {
+            for sig in Signal::iterator() {
+                assert_eq!(Signal::from_name(sig.name()).unwrap(), sig);
+            }
+            assert_eq!(Signal::from(SIGUSR1).name(), "SIGUSR1");
+            assert_eq!(Signal::from_name("term").unwrap(), SIGTERM.into());
+            assert_eq!(Signal::from_name("HUP").unwrap(), SIGHUP.into());
+            assert!(Signal::from_name("SIGFOO").is_err());
+            assert!(Signal::from_name("").is_err());
+        }
+    }
 }
 
 #[cfg(windows)]
@@ -83,6 +123,28 @@ mod windows {
                 .into()),
             }
         }
+
+        /// Parse a signal name like `SIGTERM`, `TERM` or `term`
+        pub fn from_name(name: &str) -> Result<Self, Error> {
+            let upper = name.to_ascii_uppercase();
+            match upper.strip_prefix("SIG").unwrap_or(&upper) {
+                "KILL" => Ok(Signal::SIGKILL),
+                "TERM" => Ok(Signal::SIGTERM),
+                _ => Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("unknown signal: {}", name),
+                )
+                .into()),
+            }
+        }
+
+        /// Name of the signal like `SIGTERM`
+        pub fn name(&self) -> &'static str {
+            match self {
+                Signal::SIGKILL => "SIGKILL",
+                Signal::SIGTERM => "SIGTERM",
+            }
+        }
     }
 
     impl Iterator for SignalIterator {
@@ -74,6 +74,63 @@ impl Stats {
     }
 }
 
+/// Resource usage of a container computed from `Stats`, like a row of `docker stats`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatsSummary {
+    /// cpu usage %, `None` if the previous read is not available
+    pub cpu_percent: Option<f64>,
+    /// memory usage %
+    pub mem_percent: Option<f64>,
+    /// memory usage in bytes, excluding the page cache
+    pub mem_used: Option<u64>,
+    /// memory limit in bytes
+    pub mem_limit: Option<u64>,
+    /// bytes received over all networks
+    pub net_rx: u64,
+    /// bytes sent over all networks
+    pub net_tx: u64,
+    /// bytes read from block devices
+    pub block_read: u64,
+    /// bytes written to block devices
+    pub block_write: u64,
+    /// number of pids in the cgroup
+    pub pids: Option<u64>,
+}
+
+impl From<&Stats> for StatsSummary {
+    fn from(stats: &Stats) -> Self {
+        let networks = stats.networks.iter().flat_map(|networks| networks.values());
+        let (net_rx, net_tx) = networks.fold((0, 0), |(rx, tx), network| {
+            (rx + network.rx_bytes, tx + network.tx_bytes)
+        });
+        let blkio = stats
+            .blkio_stats
+            .io_service_bytes_recursive
+            .iter()
+            .flatten();
+        let (block_read, block_write) = blkio.fold((0, 0), |(read, write), stat| {
+            if stat.op.eq_ignore_ascii_case("read") {
+                (read + stat.value, write)
+            } else if stat.op.eq_ignore_ascii_case("write") {
+                (read, write + stat.value)
+            } else {
+                (read, write)
+            }
+        });
+        StatsSummary {
+            cpu_percent: stats.cpu_usage(),
+            mem_percent: stats.memory_usage(),
+            mem_used: stats.used_memory(),
+            mem_limit: stats.available_memory(),
+            net_rx,
+            net_tx,
+            block_read,
+            block_write,
+            pids: stats.pids_stats.current,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, PartialOrd, Eq, Ord, Serialize, Deserialize)]
 pub struct Network {
     pub rx_dropped: u64,
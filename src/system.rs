@@ -1,6 +1,9 @@
+use crate::serde_helpers::null_to_default;
 use serde::de::{self, Deserializer, Visitor};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fmt;
+use std::net::IpAddr;
 use std::path::PathBuf;
 
 struct NumToBoolVisitor;
@@ -79,12 +82,160 @@ pub struct SystemInfo {
     pub NCPU: u64,
     pub MemTotal: u64,
     pub IndexServerAddress: String,
+    /// Registry configuration of the daemon
+    pub RegistryConfig: Option<RegistryConfig>,
     // pub HttpProxy: String,
     // pub HttpsProxy: String,
     // pub NoProxy: String,
     // pub Name: String,
     pub Labels: Option<Vec<String>>,
     // pub ServerVersion: String,
+    /// Plugins available on the daemon
+    pub Plugins: Option<PluginsInfo>,
+    /// Runtime name to Runtime mapping
+    pub Runtimes: Option<HashMap<String, Runtime>>,
+    /// Name of the default runtime (e.g. `runc`)
+    pub DefaultRuntime: Option<String>,
+    /// Swarm status of the daemon
+    pub Swarm: Option<SwarmInfo>,
+}
+
+impl SystemInfo {
+    /// `DriverStatus` as a map (e.g. `Backing Filesystem` to `extfs`)
+    ///
+    /// The last value wins if a key is duplicated.
+    pub fn driver_status_map(&self) -> HashMap<String, String> {
+        self.DriverStatus.iter().cloned().collect()
+    }
+}
+
+/// State of the node in a swarm
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LocalNodeState {
+    #[serde(rename = "")]
+    Empty,
+    Inactive,
+    Pending,
+    Active,
+    Error,
+    Locked,
+    /// unknown value reported by a newer daemon
+    #[serde(other)]
+    Unknown,
+}
+
+/// Swarm status of the daemon
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub struct SwarmInfo {
+    #[serde(default)]
+    pub NodeID: String,
+    #[serde(default)]
+    pub NodeAddr: String,
+    pub LocalNodeState: LocalNodeState,
+    #[serde(default)]
+    pub ControlAvailable: bool,
+    #[serde(default)]
+    pub Error: String,
+}
+
+/// Names of available plugins per kind
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub struct PluginsInfo {
+    #[serde(deserialize_with = "null_to_default", default)]
+    pub Volume: Vec<String>,
+    #[serde(deserialize_with = "null_to_default", default)]
+    pub Network: Vec<String>,
+    #[serde(deserialize_with = "null_to_default", default)]
+    pub Authorization: Vec<String>,
+    #[serde(deserialize_with = "null_to_default", default)]
+    pub Log: Vec<String>,
+}
+
+/// Registry configuration of the daemon
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub struct RegistryConfig {
+    /// Registry name to index information mapping
+    #[serde(deserialize_with = "null_to_default", default)]
+    pub IndexConfigs: HashMap<String, IndexInfo>,
+    /// Subnets of registries which are not required to use TLS
+    #[serde(deserialize_with = "null_to_default", default)]
+    pub InsecureRegistryCIDRs: Vec<String>,
+    /// Mirrors of the official registry
+    #[serde(deserialize_with = "null_to_default", default)]
+    pub Mirrors: Vec<String>,
+}
+
+/// Information of a registry index
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub struct IndexInfo {
+    pub Name: String,
+    #[serde(deserialize_with = "null_to_default", default)]
+    pub Mirrors: Vec<String>,
+    pub Secure: bool,
+    pub Official: bool,
+}
+
+fn cidr_contains(cidr: &str, addr: IpAddr) -> bool {
+    let (net, len) = match cidr.split_once('/') {
+        Some((net, len)) => (net, len),
+        None => return false,
+    };
+    let (net, len) = match (net.parse::<IpAddr>(), len.parse::<u32>()) {
+        (Ok(net), Ok(len)) => (net, len),
+        _ => return false,
+    };
+    match (net, addr) {
+        (IpAddr::V4(net), IpAddr::V4(addr)) if len <= 32 => {
+            let mask = u32::MAX.checked_shl(32 - len).unwrap_or(0);
+            u32::from(net) & mask == u32::from(addr) & mask
+        }
+        (IpAddr::V6(net), IpAddr::V6(addr)) if len <= 128 => {
+            let mask = u128::MAX.checked_shl(128 - len).unwrap_or(0);
+            u128::from(net) & mask == u128::from(addr) & mask
+        }
+        _ => false,
+    }
+}
+
+impl RegistryConfig {
+    /// Whether the daemon talks to the registry `name` (e.g. `registry.local:5000`)
+    /// without TLS verification
+    ///
+    /// # Summary
+    /// A registry is insecure if it is listed in `IndexConfigs` as not secure,
+    /// or if it is addressed by an IP address in `InsecureRegistryCIDRs`.
+    pub fn is_insecure(&self, name: &str) -> bool {
+        if let Some(index) = self.IndexConfigs.get(name) {
+            return !index.Secure;
+        }
+        let host = match name.rsplit_once(':') {
+            Some((host, port)) if port.chars().all(|c| c.is_ascii_digit()) => host,
+            _ => name,
+        };
+        let host = host.trim_start_matches('[').trim_end_matches(']');
+        match host.parse::<IpAddr>() {
+            Ok(addr) => self
+                .InsecureRegistryCIDRs
+                .iter()
+                .any(|cidr| cidr_contains(cidr, addr)),
+            Err(_) => false,
+        }
+    }
+}
+
+/// OCI runtime registered to the daemon
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Runtime {
+    /// name or path of the runtime binary
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub runtime_args: Option<Vec<String>>,
 }
 
 /// Type of the response of `/auth` api
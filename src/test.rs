@@ -1,12 +1,17 @@
 #![cfg(test)]
 
-use crate::container::{Container, ContainerInfo, HealthState};
+use crate::container::{
+    Container, ContainerInfo, ContainerState, ContainerStatus, FailureSummary, HealthState,
+    MountType, Port, PortType,
+};
 use crate::filesystem::FilesystemChange;
-use crate::image::{Image, SummaryImage};
+use crate::image::{Image, ImageFilters, ListImageFilters, SummaryImage};
 use crate::network::Network;
-use crate::options::ImageLayer;
+use crate::options::{
+    ContainerCreateOptions, ExposedPorts, ImageLayer, LabelSelector, RestartPolicy,
+};
 use crate::process::Top;
-use crate::stats::Stats;
+use crate::stats::{BlkioStat, Stats, StatsSummary};
 use crate::system::SystemInfo;
 use crate::version::Version;
 
@@ -16,12 +21,210 @@ fn get_containers() {
     assert!(serde_json::from_str::<Vec<Container>>(response).is_ok())
 }
 
+#[test]
+fn get_containers_names_trimmed() {
+    let response = get_containers_response();
+    let containers = serde_json::from_str::<Vec<Container>>(response).unwrap();
+    assert_eq!(containers[0].Names, vec!["/rust"]);
+    assert_eq!(containers[0].names_trimmed(), vec!["rust"]);
+}
+
+#[test]
+fn get_containers_sctp() {
+    let response = include_str!("fixtures/containers_response_sctp.json");
+    let containers = serde_json::from_str::<Vec<Container>>(response).unwrap();
+    let types = containers[0]
+        .Ports
+        .iter()
+        .map(|port| port.Type.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(types, vec![PortType::Tcp, PortType::Udp, PortType::Sctp]);
+    let port = serde_json::from_str::<Port>(r#"{"PrivatePort":80,"Type":"quic"}"#).unwrap();
+    assert_eq!(port.Type, PortType::Unknown);
+}
+
 #[test]
 fn get_networks() {
     let response = include_str!("fixtures/list_networks.json");
     assert!(serde_json::from_str::<Vec<Network>>(response).is_ok())
 }
 
+#[test]
+fn ser_network_filters_label() {
+    use crate::network::{LabelFilter, ListNetworkFilters, PruneNetworkFilters};
+    let mut filters = ListNetworkFilters::default();
+    filters.label("a".into()).label("b=c".into());
+    assert_eq!(
+        serde_json::to_string(&filters).unwrap(),
+        r#"{"label":["a","b=c"]}"#
+    );
+    let mut filters = PruneNetworkFilters::default();
+    filters.label(LabelFilter::with(&[("b", Some("c"))]));
+    assert_eq!(
+        serde_json::to_string(&filters).unwrap(),
+        r#"{"label":{"b=c":true}}"#
+    );
+}
+
+#[test]
+fn get_volumes() {
+    use crate::volume::{ListVolumesResponse, VolumeScope};
+    let response = include_str!("fixtures/list_volumes.json");
+    let volumes = serde_json::from_str::<ListVolumesResponse>(response).unwrap();
+    assert!(volumes.Warnings.is_empty());
+    assert_eq!(volumes.Volumes.len(), 3);
+    assert_eq!(volumes.Volumes[0].Name, "app_db");
+    assert_eq!(volumes.Volumes[0].Labels["com.docker.compose.volume"], "db");
+    assert_eq!(volumes.Volumes[0].Scope, VolumeScope::Local);
+    assert!(volumes.Volumes[1].Labels.is_empty());
+    assert_eq!(volumes.Volumes[2].Options["type"], "tmpfs");
+}
+
+#[test]
+fn ser_volume_filters() {
+    use crate::network::LabelFilter;
+    use crate::volume::{ListVolumeFilters, PruneVolumeFilters};
+    let mut filters = ListVolumeFilters::default();
+    assert!(filters.is_empty());
+    filters
+        .dangling(true)
+        .name("app".into())
+        .label(LabelFilter::with(&[("b", Some("c"))]));
+    assert_eq!(
+        serde_json::to_string(&filters).unwrap(),
+        r#"{"dangling":{"true":true},"label":{"b=c":true},"name":{"app":true}}"#
+    );
+    let mut filters = PruneVolumeFilters::default();
+    assert!(filters.is_empty());
+    filters
+        .all(true)
+        .label_not(LabelFilter::with(&[("keep", None)]));
+    assert_eq!(
+        serde_json::to_string(&filters).unwrap(),
+        r#"{"all":{"true":true},"label!":{"keep":true}}"#
+    );
+}
+
+#[test]
+fn ser_prune_filters() {
+    use crate::network::LabelFilter;
+    use crate::options::PruneFilters;
+    let mut filters = PruneFilters::default();
+    assert!(filters.is_empty());
+    filters
+        .until(vec![1600000000])
+        .label(LabelFilter::with(&[("b", Some("c"))]));
+    assert_eq!(
+        serde_json::to_string(&filters).unwrap(),
+        r#"{"until":{"1600000000":true},"label":{"b=c":true}}"#
+    );
+    assert_eq!(filters.networks().until, vec![1600000000]);
+    // volumes can not be filtered by `until`
+    assert_eq!(
+        serde_json::to_string(&filters.volumes()).unwrap(),
+        r#"{"label":{"b=c":true}}"#
+    );
+}
+
+#[test]
+fn ser_network_connect_with_ipv4() {
+    use crate::network::NetworkConnectOptions;
+    let options = NetworkConnectOptions::with_ipv4("web", std::net::Ipv4Addr::new(10, 1, 0, 5));
+    let json = serde_json::to_value(&options).unwrap();
+    assert_eq!(json["Container"], "web");
+    assert_eq!(
+        json["EndpointConfig"]["IPAMConfig"],
+        serde_json::json!({"IPv4Address": "10.1.0.5", "IPv6Address": "", "LinkLocalIPs": []})
+    );
+}
+
+#[test]
+fn get_networks_unknown_scope() {
+    use crate::network::{NetworkScope, NetworkType};
+    assert_eq!(
+        serde_json::from_str::<NetworkScope>(r#""swarm""#).unwrap(),
+        NetworkScope::Swarm
+    );
+    assert_eq!(
+        serde_json::from_str::<NetworkScope>(r#""cluster""#).unwrap(),
+        NetworkScope::Unknown
+    );
+    assert_eq!(
+        serde_json::from_str::<NetworkType>(r#""plugin""#).unwrap(),
+        NetworkType::Unknown
+    );
+}
+
+#[test]
+fn get_image_overlay_dirs() {
+    use crate::image::GraphDriver;
+    use std::path::PathBuf;
+    let response = get_image_response();
+    let image = serde_json::from_str::<Image>(response).unwrap();
+    assert!(image.GraphDriver.lower_dirs().is_empty());
+    assert_eq!(image.GraphDriver.upper_dir(), None);
+
+    let driver = serde_json::from_str::<GraphDriver>(
+        r#"{
+            "Name": "overlay2",
+            "Data": {
+                "LowerDir": "/var/lib/docker/overlay2/b1/diff:/var/lib/docker/overlay2/a0/diff",
+                "MergedDir": "/var/lib/docker/overlay2/c2/merged",
+                "UpperDir": "/var/lib/docker/overlay2/c2/diff",
+                "WorkDir": "/var/lib/docker/overlay2/c2/work"
+            }
+        }"#,
+    )
+    .unwrap();
+    assert_eq!(
+        driver.lower_dirs(),
+        vec![
+            PathBuf::from("/var/lib/docker/overlay2/b1/diff"),
+            PathBuf::from("/var/lib/docker/overlay2/a0/diff"),
+        ]
+    );
+    assert_eq!(
+        driver.merged_dir(),
+        Some(PathBuf::from("/var/lib/docker/overlay2/c2/merged"))
+    );
+    assert_eq!(
+        driver.upper_dir(),
+        Some(PathBuf::from("/var/lib/docker/overlay2/c2/diff"))
+    );
+    assert_eq!(
+        driver.work_dir(),
+        Some(PathBuf::from("/var/lib/docker/overlay2/c2/work"))
+    );
+}
+
+#[test]
+fn ser_image_filters() {
+    let mut filters = ImageFilters::new();
+    filters.stars(3);
+    assert_eq!(
+        serde_json::to_string(&filters).unwrap(),
+        r#"{"stars":["3"]}"#
+    );
+    filters.is_official(true);
+    let json = serde_json::to_string(&filters).unwrap();
+    assert_eq!(json, r#"{"is-official":["true"],"stars":["3"]}"#);
+}
+
+#[test]
+fn ser_list_image_filters() {
+    let mut filters = ListImageFilters::new();
+    assert_eq!(serde_json::to_string(&filters).unwrap(), "{}");
+    filters
+        .dangling(false)
+        .label("a")
+        .label(LabelSelector::key_value("b", "c"))
+        .reference("alpine:3.*");
+    assert_eq!(
+        serde_json::to_string(&filters).unwrap(),
+        r#"{"dangling":["false"],"label":["a","b=c"],"reference":["alpine:3.*"]}"#
+    );
+}
+
 #[test]
 fn get_stats_suspended() {
     let stats_oneshot = include_str!("fixtures/stats_suspend.json");
@@ -46,12 +249,162 @@ async fn get_stats_streaming() {
     assert!(stats[2].memory_stats.is_some());
 }
 
+#[test]
+fn get_stats_summary() {
+    let response = include_str!("fixtures/stats_stream.json");
+    let mut stats = response
+        .lines()
+        .map(|line| serde_json::from_str::<Stats>(line).unwrap());
+    let first = StatsSummary::from(&stats.next().unwrap());
+    assert_eq!(first.cpu_percent, None);
+    assert_eq!(first.mem_used, Some(8179712 - 380928));
+    assert_eq!(first.mem_limit, Some(8340705280));
+    assert_eq!(first.net_rx, 266);
+    assert_eq!(first.net_tx, 0);
+    assert_eq!(first.pids, Some(82));
+
+    let mut second = stats.next().unwrap();
+    assert!(StatsSummary::from(&second).cpu_percent.is_some());
+    second.blkio_stats.io_service_bytes_recursive = Some(
+        [
+            ("Read", 4096),
+            ("Write", 512),
+            ("read", 1024),
+            ("Total", 5632),
+        ]
+        .iter()
+        .map(|&(op, value)| BlkioStat {
+            major: 8,
+            minor: 0,
+            op: op.to_owned(),
+            value,
+        })
+        .collect(),
+    );
+    let summary = StatsSummary::from(&second);
+    assert_eq!(summary.block_read, 5120);
+    assert_eq!(summary.block_write, 512);
+}
+
+#[tokio::test]
+async fn attach_frame_stdcopy() {
+    use crate::container::{AttachResponseFrame, ContainerStdioType};
+    use futures::stream::StreamExt;
+    let frames = vec![
+        AttachResponseFrame {
+            type_: ContainerStdioType::Stdout,
+            frame: b"hello\n".to_vec(),
+        },
+        AttachResponseFrame {
+            type_: ContainerStdioType::Stderr,
+            frame: vec![0, 1, 2],
+        },
+    ];
+    assert_eq!(frames[0].payload_len(), 6);
+    assert_eq!(frames[0].header(), [1, 0, 0, 0, 0, 0, 0, 6]);
+    let body = frames
+        .iter()
+        .flat_map(|frame| frame.to_stdcopy())
+        .collect::<Vec<u8>>();
+    let decoded = crate::docker::into_aframe_stream(hyper::Body::from(body))
+        .await
+        .unwrap()
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(decoded, frames);
+}
+
+#[test]
+fn get_pull_progress() {
+    use crate::response::{ProgressDetail, PullProgress, Response};
+    let progress = include_str!("fixtures/pull_progress.json")
+        .lines()
+        .map(|line| serde_json::from_str::<Response>(line).unwrap())
+        .filter_map(PullProgress::from_response)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(progress.len(), 9);
+    assert_eq!(progress[1].detail, None);
+    assert_eq!(
+        progress[4].detail,
+        Some(ProgressDetail {
+            current: None,
+            total: Some(2797541),
+        })
+    );
+    assert_eq!(progress[5].detail.as_ref().unwrap().current, Some(2797541));
+}
+
 #[test]
 fn get_system_info() {
     let response = get_system_info_response();
     assert!(serde_json::from_str::<SystemInfo>(response).is_ok())
 }
 
+#[test]
+fn get_system_info_runtimes() {
+    let response = get_system_info_runtimes_response();
+    let info = serde_json::from_str::<SystemInfo>(response).unwrap();
+    assert_eq!(info.DefaultRuntime.as_deref(), Some("runc"));
+    let runtimes = info.Runtimes.unwrap();
+    assert_eq!(runtimes["nvidia"].path, "/usr/bin/nvidia-container-runtime");
+    assert!(runtimes["runc"].runtime_args.is_none());
+    let plugins = info.Plugins.unwrap();
+    assert!(plugins.Log.iter().any(|log| log == "json-file"));
+    assert!(plugins.Authorization.is_empty());
+
+    let info = serde_json::from_str::<SystemInfo>(get_system_info_response()).unwrap();
+    assert!(info.Runtimes.is_none());
+}
+
+#[test]
+fn get_system_info_driver_status() {
+    let response = get_system_info_runtimes_response();
+    let info = serde_json::from_str::<SystemInfo>(response).unwrap();
+    let status = info.driver_status_map();
+    assert_eq!(status["Backing Filesystem"], "extfs");
+    assert_eq!(status["Supports d_type"], "true");
+    assert_eq!(status.len(), info.DriverStatus.len());
+}
+
+#[test]
+fn get_system_info_swarm() {
+    use crate::system::LocalNodeState;
+    let response = get_system_info_runtimes_response();
+    let info = serde_json::from_str::<SystemInfo>(response).unwrap();
+    assert_eq!(info.Swarm.unwrap().LocalNodeState, LocalNodeState::Inactive);
+    assert_eq!(
+        serde_json::from_str::<LocalNodeState>(r#""active""#).unwrap(),
+        LocalNodeState::Active
+    );
+    assert_eq!(
+        serde_json::from_str::<LocalNodeState>(r#""""#).unwrap(),
+        LocalNodeState::Empty
+    );
+    assert_eq!(
+        serde_json::from_str::<LocalNodeState>(r#""joining""#).unwrap(),
+        LocalNodeState::Unknown
+    );
+}
+
+#[test]
+fn get_system_info_registry_config() {
+    let response = get_system_info_runtimes_response();
+    let info = serde_json::from_str::<SystemInfo>(response).unwrap();
+    let registry = info.RegistryConfig.unwrap();
+    assert_eq!(registry.InsecureRegistryCIDRs, vec!["127.0.0.0/8"]);
+    assert_eq!(registry.Mirrors, vec!["https://mirror.gcr.io/"]);
+    assert!(registry.IndexConfigs["docker.io"].Official);
+    assert!(!registry.is_insecure("docker.io"));
+    assert!(registry.is_insecure("registry.local:5000"));
+    assert!(registry.is_insecure("127.0.0.1:5000"));
+    assert!(!registry.is_insecure("10.0.0.1:5000"));
+    assert!(!registry.is_insecure("example.com"));
+}
+
 #[test]
 fn get_image_list() {
     let response = get_image_list_response();
@@ -59,12 +412,41 @@ fn get_image_list() {
     assert_eq!(3, images.len());
 }
 
+#[test]
+fn get_image_tags() {
+    let response = get_image_list_response();
+    let mut images: Vec<SummaryImage> = serde_json::from_str(response).unwrap();
+    // a dangling image
+    images[1].RepoTags = vec!["<none>:<none>".to_owned()];
+    assert_eq!(
+        crate::docker::repo_tags(&images),
+        vec!["ghmlee/rust:nightly"]
+    );
+}
+
 #[test]
 fn get_image() {
     let response = get_image_response();
     println!("response: {:?}", serde_json::from_str::<Image>(response));
 }
 
+#[test]
+fn expose_image_ports() {
+    let mut image = serde_json::from_str::<Image>(get_image_response()).unwrap();
+    image.Config.ExposedPorts = ["80/tcp", "53/udp"]
+        .iter()
+        .map(|key| (key.to_string(), Default::default()))
+        .collect();
+    let mut options = ContainerCreateOptions::new("debian");
+    options.exposed_ports(ExposedPorts(vec![(80, "tcp".to_owned())]));
+    options.expose_image_ports(&image);
+    let json = serde_json::to_value(&options).unwrap();
+    assert_eq!(
+        json["ExposedPorts"],
+        serde_json::json!({"80/tcp": {}, "53/udp": {}})
+    );
+}
+
 #[test]
 fn get_image_history() {
     let response = get_image_history_reponse();
@@ -82,6 +464,219 @@ fn get_container_info() {
     serde_json::from_str::<ContainerInfo>(response).unwrap();
 }
 
+#[test]
+fn get_container_restart_policy() {
+    let response = get_container_info_response();
+    let container_info = serde_json::from_str::<ContainerInfo>(response).unwrap();
+    assert_eq!(
+        container_info.HostConfig.RestartPolicy,
+        RestartPolicy::new("".to_owned(), 0)
+    );
+    assert_eq!(container_info.HostConfig.NetworkMode, "railshello_default");
+}
+
+#[test]
+fn get_container_env_and_labels() {
+    let response = get_container_info_response();
+    let container_info = serde_json::from_str::<ContainerInfo>(response).unwrap();
+    let env = container_info.env_map();
+    assert_eq!(env["RACK_ENV"], "development");
+    assert_eq!(
+        env["DATABASE_URL"],
+        "postgres://postgres@db:5432/rails_hello_development"
+    );
+    assert_eq!(
+        container_info.label("com.docker.compose.service"),
+        Some("web")
+    );
+    assert_eq!(container_info.label("com.example.missing"), None);
+}
+
+#[test]
+fn get_container_command_line() {
+    let response = get_container_info_response();
+    let mut container_info = serde_json::from_str::<ContainerInfo>(response).unwrap();
+    assert_eq!(
+        container_info.command_line(),
+        vec!["rails", "server", "-b", "0.0.0.0"]
+    );
+    assert_eq!(container_info.effective_entrypoint(), vec!["rails"]);
+    container_info.Config.Entrypoint = vec!["/entrypoint.sh".to_owned()];
+    assert_eq!(
+        container_info.effective_entrypoint(),
+        vec!["/entrypoint.sh"]
+    );
+}
+
+#[test]
+fn get_container_ip_address() {
+    let response = get_container_info_response();
+    let mut container_info = serde_json::from_str::<ContainerInfo>(response).unwrap();
+    assert_eq!(
+        container_info.ip_address("railshello_default"),
+        Some("172.24.0.3")
+    );
+    assert_eq!(container_info.ip_address("bridge"), None);
+    let addresses = container_info.ip_addresses();
+    assert_eq!(addresses.len(), 1);
+    assert_eq!(addresses["railshello_default"], "172.24.0.3");
+
+    container_info
+        .NetworkSettings
+        .Networks
+        .get_mut("railshello_default")
+        .unwrap()
+        .IPAddress = String::new();
+    assert_eq!(container_info.ip_address("railshello_default"), None);
+    assert!(container_info.ip_addresses().is_empty());
+}
+
+#[test]
+fn get_container_crash_looping() {
+    let response = get_container_info_response();
+    let mut container_info = serde_json::from_str::<ContainerInfo>(response).unwrap();
+    assert!(!container_info.is_crash_looping(0));
+    assert_eq!(container_info.max_restart_retries(), None);
+
+    container_info.State.Status = "restarting".to_owned();
+    container_info.State.Restarting = true;
+    container_info.RestartCount = 5;
+    container_info.HostConfig.RestartPolicy = RestartPolicy::new("on-failure".to_owned(), 10);
+    assert!(container_info.is_crash_looping(5));
+    assert!(!container_info.is_crash_looping(6));
+    assert_eq!(container_info.max_restart_retries(), Some(10));
+}
+
+#[test]
+fn get_container_failure_summary() {
+    let response = get_container_info_response();
+    let mut container_info = serde_json::from_str::<ContainerInfo>(response).unwrap();
+    assert_eq!(
+        container_info.failure_summary(),
+        FailureSummary {
+            restart_count: 0,
+            exit_code: 0,
+            error: None,
+            oom_killed: false,
+            finished_at: None,
+        }
+    );
+
+    container_info.RestartCount = 3;
+    container_info.State.ExitCode = 137;
+    container_info.State.OOMKilled = true;
+    container_info.State.Error = "oom".to_owned();
+    container_info.State.FinishedAt = "2016-10-25T12:00:00Z".to_owned();
+    let summary = container_info.failure_summary();
+    assert_eq!(summary.restart_count, 3);
+    assert_eq!(summary.exit_code, 137);
+    assert_eq!(summary.error.as_deref(), Some("oom"));
+    assert!(summary.oom_killed);
+    assert_eq!(
+        summary.finished_at.unwrap().to_rfc3339(),
+        "2016-10-25T12:00:00+00:00"
+    );
+}
+
+#[test]
+fn get_container_host_port() {
+    let response = get_container_info_response();
+    let container_info = serde_json::from_str::<ContainerInfo>(response).unwrap();
+    assert_eq!(container_info.host_port(3000, PortType::Tcp), Some(3000));
+    assert_eq!(container_info.host_port(3000, PortType::Udp), None);
+    assert_eq!(container_info.host_port(80, PortType::Tcp), None);
+}
+
+#[test]
+fn get_container_log_path() {
+    let response = get_container_info_response();
+    let mut container_info = serde_json::from_str::<ContainerInfo>(response).unwrap();
+    assert_eq!(
+        container_info.log_path(),
+        Some(std::path::Path::new(&container_info.LogPath))
+    );
+    container_info.LogPath = String::new();
+    assert_eq!(container_info.log_path(), None);
+}
+
+#[test]
+fn parse_container_status() {
+    let containers = serde_json::from_str::<Vec<Container>>(get_containers_response()).unwrap();
+    let states = containers
+        .iter()
+        .map(|container| container.State.parse::<ContainerStatus>())
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(states, vec![ContainerStatus::Exited]);
+    for status in [ContainerStatus::Created, ContainerStatus::Exited] {
+        assert_eq!(status.to_string().parse(), Ok(status.clone()));
+        assert_eq!(
+            serde_json::to_string(&status).unwrap(),
+            format!("\"{status}\"")
+        );
+    }
+    assert!("hibernating".parse::<ContainerStatus>().is_err());
+    assert_eq!(
+        serde_json::from_str::<ContainerStatus>("\"hibernating\"").unwrap(),
+        ContainerStatus::Unknown
+    );
+}
+
+#[test]
+fn get_container_state() {
+    let response = get_container_info_response();
+    let mut container_info = serde_json::from_str::<ContainerInfo>(response).unwrap();
+    assert_eq!(container_info.State.status_enum(), ContainerState::Running);
+    container_info.State.Status = "removing".to_owned();
+    assert_eq!(container_info.State.status_enum(), ContainerState::Removing);
+    container_info.State.Status = "hibernating".to_owned();
+    assert_eq!(container_info.State.status_enum(), ContainerState::Unknown);
+}
+
+#[test]
+fn get_container_state_times() {
+    let response = get_container_info_response();
+    let mut container_info = serde_json::from_str::<ContainerInfo>(response).unwrap();
+    let started_at = container_info.State.started_at().unwrap();
+    assert_eq!(
+        started_at.to_rfc3339(),
+        "2016-10-25T11:59:38.261828009+00:00"
+    );
+    assert_eq!(container_info.State.finished_at(), None);
+    assert!(container_info.State.uptime().unwrap().as_secs() > 0);
+    container_info.State.Running = false;
+    assert_eq!(container_info.State.uptime(), None);
+}
+
+#[test]
+fn get_container_stop_config() {
+    let response = get_container_info_response_with_healthcheck();
+    let container_info = serde_json::from_str::<ContainerInfo>(response).unwrap();
+    assert_eq!(container_info.Config.StopSignal.as_deref(), Some("SIGINT"));
+    assert_eq!(container_info.Config.StopTimeout, Some(30));
+
+    let response = get_container_info_response();
+    let container_info = serde_json::from_str::<ContainerInfo>(response).unwrap();
+    assert_eq!(container_info.Config.StopSignal, None);
+    assert_eq!(container_info.Config.StopTimeout, None);
+}
+
+#[test]
+fn get_container_mounts() {
+    let response = include_str!("fixtures/container_inspect_mounts.json");
+    let container_info = serde_json::from_str::<ContainerInfo>(response).unwrap();
+    let mounts = &container_info.Mounts;
+    assert_eq!(mounts.len(), 2);
+    assert_eq!(mounts[0].Type, Some(MountType::Volume));
+    assert_eq!(mounts[0].Name.as_deref(), Some("railshello_db-data"));
+    assert_eq!(mounts[0].Driver.as_deref(), Some("local"));
+    assert!(mounts[0].RW);
+    assert_eq!(mounts[1].Type, Some(MountType::Bind));
+    assert_eq!(mounts[1].Name, None);
+    assert_eq!(mounts[1].Source, "/home/user/railshello");
+    assert!(!mounts[1].RW);
+}
+
 #[test]
 fn get_healthcheck_info() {
     let response = get_container_info_response_with_healthcheck();
@@ -108,6 +703,48 @@ fn get_version() {
     assert!(serde_json::from_str::<Version>(response).is_ok())
 }
 
+#[test]
+fn ser_version() {
+    let response = get_version_response();
+    let version = serde_json::from_str::<Version>(response).unwrap();
+    let json = serde_json::to_value(&version).unwrap();
+    assert_eq!(json["ApiVersion"], "1.20");
+    assert_eq!(json["MinAPIVersion"], "");
+    assert!(json.get("Experimental").is_none());
+    let version = serde_json::from_value::<Version>(json).unwrap();
+    assert_eq!(
+        version.BuildTime.as_deref(),
+        Some("Thu Aug 13 02:49:29 UTC 2015")
+    );
+}
+
+#[test]
+fn get_version_components() {
+    let version = serde_json::from_str::<Version>(get_version_response()).unwrap();
+    assert!(version.Components.is_none());
+    assert!(version.component("containerd").is_none());
+
+    let response = include_str!("fixtures/version_components.json");
+    let version = serde_json::from_str::<Version>(response).unwrap();
+    assert_eq!(version.Components.as_ref().unwrap().len(), 4);
+    assert_eq!(version.component("containerd").unwrap().Version, "1.6.21");
+    let runc = version.component("runc").unwrap();
+    assert_eq!(runc.Version, "1.1.7");
+    assert_eq!(
+        runc.Details.as_ref().unwrap()["GitCommit"],
+        "v1.1.7-0-g860f061"
+    );
+}
+
+#[test]
+fn ser_event() {
+    use crate::event::EventResponse;
+    let event = r#"{"Type":"container","Action":"start","Actor":{"ID":"abc","Attributes":{"name":"web"}},"time":1600000000,"timeNano":1600000000000000000}"#;
+    let json = serde_json::from_str::<serde_json::Value>(event).unwrap();
+    let event = serde_json::from_value::<EventResponse>(json.clone()).unwrap();
+    assert_eq!(serde_json::to_value(&event).unwrap(), json);
+}
+
 fn get_containers_response() -> &'static str {
     include_str!("fixtures/containers_response.json")
 }
@@ -116,6 +753,10 @@ fn get_system_info_response() -> &'static str {
     include_str!("fixtures/system_info.json")
 }
 
+fn get_system_info_runtimes_response() -> &'static str {
+    include_str!("fixtures/system_info_runtimes.json")
+}
+
 // `docker inspect debian:wheely-2019- |  jq '.[]'
 fn get_image_response() -> &'static str {
     include_str!("fixtures/image.json")
@@ -1,6 +1,8 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use serde_json::value as json;
+use std::collections::HashMap;
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[allow(non_snake_case)]
 pub struct Version {
     pub Version: String,
@@ -12,6 +14,32 @@ pub struct Version {
     pub Os: String,
     pub Arch: String,
     pub KernelVersion: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub Experimental: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub BuildTime: Option<String>,
+    /// Components of the daemon like `Engine`, `containerd` and `runc`.
+    /// Not reported by old daemons.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub Components: Option<Vec<VersionComponent>>,
+}
+
+impl Version {
+    /// Component named `name` like `containerd`
+    pub fn component(&self, name: &str) -> Option<&VersionComponent> {
+        self.Components
+            .as_ref()?
+            .iter()
+            .find(|component| component.Name == name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub struct VersionComponent {
+    pub Name: String,
+    pub Version: String,
+    /// Component specific details like `GitCommit`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub Details: Option<HashMap<String, json::Value>>,
 }
@@ -0,0 +1,240 @@
+#![allow(clippy::new_without_default)]
+use crate::network::LabelFilter;
+use crate::serde_helpers::null_to_default;
+use serde::{Deserialize, Serialize};
+use serde_json::value as json;
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub struct Volume {
+    pub Name: String,
+    pub Driver: String,
+    /// Mount path of the volume on the host
+    pub Mountpoint: String,
+    /// Time the volume was created (not reported by old daemons)
+    #[serde(default)]
+    pub CreatedAt: Option<String>,
+    /// Low-level details reported by the volume driver
+    #[serde(default)]
+    pub Status: Option<HashMap<String, json::Value>>,
+    #[serde(deserialize_with = "null_to_default", default)]
+    pub Labels: HashMap<String, String>,
+    pub Scope: VolumeScope,
+    #[serde(deserialize_with = "null_to_default", default)]
+    pub Options: HashMap<String, String>,
+    /// Usage of the volume, reported only by `/system/df`
+    #[serde(default)]
+    pub UsageData: Option<VolumeUsageData>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub struct VolumeUsageData {
+    /// Disk usage in bytes, `-1` if it is not calculated
+    pub Size: i64,
+    /// Number of containers referring to the volume, `-1` if it is not calculated
+    pub RefCount: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VolumeScope {
+    Local,
+    Global,
+    /// unknown value reported by a newer daemon
+    #[serde(other)]
+    Unknown,
+}
+
+/// Response of /volumes api
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[allow(non_snake_case)]
+pub struct ListVolumesResponse {
+    #[serde(deserialize_with = "null_to_default", default)]
+    pub Volumes: Vec<Volume>,
+    /// Warnings of the volume drivers which failed to list their volumes
+    #[serde(deserialize_with = "null_to_default", default)]
+    pub Warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ListVolumeFilters {
+    pub dangling: Option<bool>,
+    pub driver: Vec<String>,
+    pub label: LabelFilter,
+    pub name: Vec<String>,
+}
+
+impl ListVolumeFilters {
+    pub fn is_empty(&self) -> bool {
+        self.dangling.is_none()
+            && self.driver.is_empty()
+            && self.label.is_empty()
+            && self.name.is_empty()
+    }
+
+    /// `true`: volumes not referenced by any container, `false`: volumes referenced by a container
+    pub fn dangling(&mut self, dangling: bool) -> &mut Self {
+        self.dangling = Some(dangling);
+        self
+    }
+
+    pub fn driver(&mut self, driver: Cow<str>) -> &mut Self {
+        self.driver.push(driver.into_owned());
+        self
+    }
+
+    pub fn label(&mut self, label: LabelFilter) -> &mut Self {
+        self.label = label;
+        self
+    }
+
+    /// filter by a part of the volume name
+    pub fn name(&mut self, name: Cow<str>) -> &mut Self {
+        self.name.push(name.into_owned());
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct PruneVolumeFilters {
+    /// Prune named volumes too, not only anonymous ones (API 1.42+)
+    pub all: bool,
+    pub label: LabelFilter,
+    pub label_not: LabelFilter,
+}
+
+impl PruneVolumeFilters {
+    pub fn is_empty(&self) -> bool {
+        !self.all && self.label.is_empty() && self.label_not.is_empty()
+    }
+
+    pub fn all(&mut self, all: bool) -> &mut Self {
+        self.all = all;
+        self
+    }
+
+    pub fn label(&mut self, label: LabelFilter) -> &mut Self {
+        self.label = label;
+        self
+    }
+
+    pub fn label_not(&mut self, label_not: LabelFilter) -> &mut Self {
+        self.label_not = label_not;
+        self
+    }
+}
+
+/// request body of /volumes/create api
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct VolumeCreateOptions {
+    /// Empty name is generated by the daemon
+    pub name: String,
+    pub driver: String,
+    pub driver_opts: HashMap<String, String>,
+    pub labels: HashMap<String, String>,
+}
+
+impl VolumeCreateOptions {
+    /// equivalent to `docker volume create <name>`
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            driver: "local".to_owned(),
+            driver_opts: HashMap::new(),
+            labels: HashMap::new(),
+        }
+    }
+
+    pub fn driver(&mut self, driver: &str) -> &mut Self {
+        self.driver = driver.to_owned();
+        self
+    }
+
+    /// equivalent to `--opt` option
+    pub fn driver_opt(&mut self, key: &str, value: &str) -> &mut Self {
+        self.driver_opts.insert(key.to_owned(), value.to_owned());
+        self
+    }
+
+    pub fn label(&mut self, key: &str, value: &str) -> &mut Self {
+        self.labels.insert(key.to_owned(), value.to_owned());
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct PruneVolumeResponse {
+    #[serde(deserialize_with = "null_to_default")]
+    pub volumes_deleted: Vec<String>,
+    pub space_reclaimed: i64,
+}
+
+mod format {
+    use super::*;
+
+    use serde::{ser::*, Serialize, Serializer};
+
+    impl Serialize for ListVolumeFilters {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            // every filter is serialized as a map like `LabelFilter`,
+            // since the daemon rejects filters mixing maps and lists
+            let mut state = serializer.serialize_map(None)?;
+            if let Some(dangling) = self.dangling {
+                state.serialize_entry("dangling", &TrueSet(&[dangling.to_string()]))?;
+            }
+            if !self.driver.is_empty() {
+                state.serialize_entry("driver", &TrueSet(&self.driver))?;
+            }
+            if !self.label.is_empty() {
+                state.serialize_entry("label", &self.label)?;
+            }
+            if !self.name.is_empty() {
+                state.serialize_entry("name", &TrueSet(&self.name))?;
+            }
+            state.end()
+        }
+    }
+
+    impl Serialize for PruneVolumeFilters {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            let mut state = serializer.serialize_map(None)?;
+            if self.all {
+                state.serialize_entry("all", &TrueSet(&["true".to_owned()]))?;
+            }
+            if !self.label.is_empty() {
+                state.serialize_entry("label", &self.label)?;
+            }
+            if !self.label_not.is_empty() {
+                state.serialize_entry("label!", &self.label_not)?;
+            }
+            state.end()
+        }
+    }
+
+    #[derive(Debug, Clone)]
+    struct TrueSet<'a>(&'a [String]);
+
+    impl<'a> Serialize for TrueSet<'a> {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            let mut map = serializer.serialize_map(Some(self.0.len()))?;
+            for value in self.0 {
+                map.serialize_entry(value, &true)?;
+            }
+            map.end()
+        }
+    }
+}